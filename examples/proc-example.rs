@@ -3,6 +3,7 @@ use chumsky_proc::prelude::*;
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Punct};
 
+#[allow(dead_code)]
 #[derive(Debug)]
 struct SimpleExpr {
     left: Ident,
@@ -1,3 +1,5 @@
+#![allow(clippy::result_large_err)]
+
 use chumsky::prelude::*;
 use chumsky_proc::prelude::*;
 use proc_macro::TokenStream;
@@ -5,29 +7,20 @@ use proc_macro2::{Delimiter, Literal};
 use quote::{quote, ToTokens};
 use std::str::FromStr;
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Keyword)]
 enum UnitName {
     S,
     M,
     Kg,
+    #[keyword = "A"]
     A,
+    #[keyword = "K"]
     K,
     Mol,
     Cd,
 }
 
 impl UnitName {
-    fn parser() -> impl Parser<RustToken, UnitName, Error = Simple<RustToken, RustSpan>> + Clone {
-        keyword("s")
-            .to(UnitName::S)
-            .or(keyword("m").to(UnitName::M))
-            .or(keyword("kg").to(UnitName::Kg))
-            .or(keyword("A").to(UnitName::A))
-            .or(keyword("K").to(UnitName::K))
-            .or(keyword("mol").to(UnitName::Mol))
-            .or(keyword("cd").to(UnitName::Cd))
-    }
-
     fn eval(&self) -> UnitResult {
         match self {
             UnitName::S => UnitResult {
@@ -72,14 +65,17 @@ enum UnitExpr {
 }
 
 impl UnitExpr {
-    fn parser() -> impl Parser<RustToken, UnitExpr, Error = Simple<RustToken, RustSpan>> {
+    fn parser() -> impl Parser<RustToken, UnitExpr, Error = RustError> {
         recursive(|expr| {
-            let atom = UnitName::parser().map(UnitExpr::Unit).or(expr
-                .delimited_by(
-                    just(RustToken::StartDelim(Delimiter::Parenthesis)),
-                    just(RustToken::EndDelim(Delimiter::Parenthesis)),
-                )
-                .map(|expr| UnitExpr::Paren(Box::new(expr))));
+            let atom = UnitName::parser()
+                .labelled("unit")
+                .map(UnitExpr::Unit)
+                .or(expr
+                    .delimited_by(
+                        just(RustToken::StartDelim(Delimiter::Parenthesis)),
+                        just(RustToken::EndDelim(Delimiter::Parenthesis)),
+                    )
+                    .map(|expr| UnitExpr::Paren(Box::new(expr))));
 
             let pow = atom
                 .then(
@@ -97,7 +93,7 @@ impl UnitExpr {
                                             }
                                         })
                                         .map_err(|tok| {
-                                            Simple::expected_input_found(span, [], Some(tok))
+                                            RustError::expected_input_found(span, [], Some(tok))
                                         })
                                 }))
                                 .map(|(neg, val)| {
@@ -120,12 +116,9 @@ impl UnitExpr {
                 .then(pow.repeated())
                 .foldl(|left, right| UnitExpr::Mul(Box::new(left), Box::new(right)));
 
-            let div = mul
-                .clone()
+            mul.clone()
                 .then(punct('/').ignore_then(mul).repeated())
-                .foldl(|left, right| UnitExpr::Div(Box::new(left), Box::new(right)));
-
-            div
+                .foldl(|left, right| UnitExpr::Div(Box::new(left), Box::new(right)))
         })
     }
 
@@ -254,13 +247,10 @@ impl ToTokens for UnitResult {
 pub fn Unit(stream: TokenStream) -> TokenStream {
     match UnitExpr::parser().parse(stream_from_tokens(stream.into())) {
         Ok(expr) => expr.eval().into_token_stream().into(),
-        Err(errs) => {
-            let msg = errs
-                .into_iter()
-                .map(|e| format!("{:?}", e))
-                .collect::<String>();
-
-            quote!(compile_error!(#msg)).into()
-        }
+        Err(errs) => errs
+            .iter()
+            .map(RustError::to_compile_error)
+            .collect::<proc_macro2::TokenStream>()
+            .into(),
     }
 }
@@ -0,0 +1,28 @@
+//! A simple spanned error, rendered as a `compile_error!`
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote_spanned;
+
+/// An error in the input to one of our macros
+pub(crate) struct Error {
+    /// Where the error occurred
+    span: Span,
+    /// The message to show
+    message: String,
+}
+
+impl Error {
+    /// Create an error with a message at the given span
+    pub(crate) fn new(span: Span, message: impl ToString) -> Error {
+        Error {
+            span,
+            message: message.to_string(),
+        }
+    }
+
+    /// Render this error as a `compile_error!` invocation at its span
+    pub(crate) fn to_compile_error(&self) -> TokenStream {
+        let message = &self.message;
+        quote_spanned!(self.span=> ::core::compile_error! { #message })
+    }
+}
@@ -0,0 +1,576 @@
+//! The `grammar!` macro, compiling PEG-style rules into mutually recursive parsers
+
+use proc_macro2::{Delimiter, Ident, Literal, Spacing, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+use std::iter::Peekable;
+
+use crate::error::Error;
+use crate::keyword::is_ident;
+use crate::parse::PUNCT_CHARS;
+use crate::tokens::{builtin, KINDS};
+
+/// The token iterator used while parsing
+type Trees = Peekable<std::vec::IntoIter<TokenTree>>;
+
+/// The path to chumsky's `Parser` trait, for calling its methods without importing it
+fn parser_trait() -> TokenStream {
+    quote!(::chumsky_proc::__private::chumsky::Parser)
+}
+
+/// A single rule of a grammar
+struct Rule {
+    /// The outer attributes of the rule, other than `#[label]`
+    attrs: Vec<TokenStream>,
+    /// The label given with `#[label = "..."]`, if there is one
+    label: Option<Literal>,
+    /// The visibility of the rule, which is public if it isn't empty
+    vis: TokenStream,
+    /// The name of the rule
+    name: Ident,
+    /// The output type of the rule, which is `()` if it isn't given
+    ty: TokenStream,
+    /// The tokens after the `=`, without the final `;`
+    body: Vec<TokenTree>,
+}
+
+/// Expand a `grammar!` invocation into a struct holding the grammar's public rules
+pub(crate) fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+    let mut trees = input.into_iter().collect::<Vec<_>>().into_iter().peekable();
+
+    let attrs = outer_attrs(&mut trees);
+    let vis = vis(&mut trees);
+    expect_keyword(
+        &mut trees,
+        "struct",
+        "expected the grammar's name, like `pub struct Calc;`",
+    )?;
+    let name = expect_ident(&mut trees, "expected the grammar's name")?;
+    expect_punct(&mut trees, ';', "expected `;` after the grammar's name")?;
+
+    let mut rules = Vec::new();
+    while trees.peek().is_some() {
+        rules.push(rule(&mut trees)?);
+    }
+
+    let names = rules.iter().map(|rule| &rule.name).collect::<Vec<_>>();
+    for (idx, rule) in rules.iter().enumerate() {
+        if is_delimiter(&rule.name) {
+            return Err(Error::new(
+                rule.name.span(),
+                format!(
+                    "`{}` matches a delimited group, so can't be a rule name",
+                    rule.name
+                ),
+            ));
+        }
+        if names[..idx].contains(&&rule.name) {
+            return Err(Error::new(
+                rule.name.span(),
+                format!("duplicate rule `{}`", rule.name),
+            ));
+        }
+    }
+
+    let public = rules
+        .iter()
+        .filter(|rule| !rule.vis.is_empty())
+        .collect::<Vec<_>>();
+    if public.is_empty() {
+        return Err(Error::new(
+            name.span(),
+            "a grammar needs at least one public rule, like `pub rule expr = ...;`",
+        ));
+    }
+
+    let parser = parser_trait();
+    let mut defines = Vec::with_capacity(rules.len());
+    for rule in &rules {
+        let rule_name = &rule.name;
+        let mut body = choice(rule.body.clone(), &names)?;
+        if rule.ty.to_string() == "()" {
+            body = quote!(#parser::ignored(#body));
+        }
+        if let Some(label) = &rule.label {
+            body = quote!(#parser::labelled(#body, #label));
+        }
+        defines.push(quote!(#rule_name.define(#body);));
+    }
+
+    let declares = rules.iter().map(|rule| {
+        let (rule_name, ty) = (&rule.name, &rule.ty);
+        quote! {
+            let mut #rule_name = ::chumsky_proc::__private::chumsky::recursive::Recursive::<
+                'static,
+                ::chumsky_proc::RustToken,
+                #ty,
+                E,
+            >::declare();
+        }
+    });
+
+    let fields = public.iter().map(|rule| {
+        let (attrs, vis, rule_name, ty) = (&rule.attrs, &rule.vis, &rule.name, &rule.ty);
+        quote! {
+            #(#attrs)*
+            #vis #rule_name: ::chumsky_proc::__private::chumsky::BoxedParser<
+                'static,
+                ::chumsky_proc::RustToken,
+                #ty,
+                E,
+            >
+        }
+    });
+    let public_names = public.iter().map(|rule| &rule.name);
+
+    let bounds = quote! {
+        E: ::chumsky_proc::__private::chumsky::error::Error<
+                ::chumsky_proc::RustToken,
+                Span = ::chumsky_proc::RustSpan,
+                Label = &'static str,
+            > + 'static
+    };
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis struct #name<#bounds> {
+            #(#fields,)*
+        }
+
+        impl<#bounds> #name<E> {
+            /// Build the grammar
+            #[must_use]
+            pub fn new() -> Self {
+                #(#declares)*
+                #(#defines)*
+                #name {
+                    #(#public_names: #parser::boxed(#public_names),)*
+                }
+            }
+        }
+
+        impl<#bounds> ::core::default::Default for #name<E> {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    })
+}
+
+/// Parse a single rule, such as `pub rule expr -> Expr = term ("+" term)*;`
+fn rule(trees: &mut Trees) -> Result<Rule, Error> {
+    let mut attrs = Vec::new();
+    let mut label = None;
+    for attr in outer_attrs(trees) {
+        match label_attr(&attr)? {
+            Some(lit) => label = Some(lit),
+            None => attrs.push(attr),
+        }
+    }
+
+    let vis = vis(trees);
+    expect_keyword(trees, "rule", "expected a rule, like `rule name = ...;`")?;
+    let name = expect_ident(trees, "expected the rule's name")?;
+
+    let mut ty = quote!(());
+    if trees.peek().is_some_and(|tree| is_punct(tree, '-')) {
+        trees.next();
+        expect_punct(trees, '>', "expected `->` before the rule's output type")?;
+        ty = until_eq(trees);
+        if ty.is_empty() {
+            return Err(Error::new(name.span(), "expected the rule's output type"));
+        }
+    }
+    expect_punct(trees, '=', "expected `=` before the rule's definition")?;
+
+    let mut body = Vec::new();
+    loop {
+        match trees.next() {
+            Some(tree) if is_punct(&tree, ';') => break,
+            Some(tree) => body.push(tree),
+            None => {
+                return Err(Error::new(
+                    name.span(),
+                    format!("expected `;` after the definition of `{}`", name),
+                ))
+            }
+        }
+    }
+    if body.is_empty() {
+        return Err(Error::new(
+            name.span(),
+            format!("the rule `{}` is empty", name),
+        ));
+    }
+
+    Ok(Rule {
+        attrs,
+        label,
+        vis,
+        name,
+        ty,
+        body,
+    })
+}
+
+/// Parse any outer attributes, keeping each as the tokens of the whole attribute
+fn outer_attrs(trees: &mut Trees) -> Vec<TokenStream> {
+    let mut attrs = Vec::new();
+    while trees.peek().is_some_and(|tree| is_punct(tree, '#')) {
+        let pound = trees.next();
+        let group = trees.next();
+        attrs.push(pound.into_iter().chain(group).collect());
+    }
+    attrs
+}
+
+/// Get the label of an attribute written like `#[label = "an expression"]`
+fn label_attr(attr: &TokenStream) -> Result<Option<Literal>, Error> {
+    let Some(TokenTree::Group(group)) = attr.clone().into_iter().nth(1) else {
+        return Ok(None);
+    };
+    let mut inner = group.stream().into_iter();
+    match inner.next() {
+        Some(TokenTree::Ident(name)) if name == "label" => (),
+        _ => return Ok(None),
+    }
+    match (inner.next(), inner.next(), inner.next()) {
+        (Some(eq), Some(TokenTree::Literal(lit)), None)
+            if is_punct(&eq, '=') && lit.to_string().starts_with('"') =>
+        {
+            Ok(Some(lit))
+        }
+        _ => Err(Error::new(
+            group.span(),
+            "expected a label, like `#[label = \"an expression\"]`",
+        )),
+    }
+}
+
+/// Parse a possibly empty visibility, such as `pub` or `pub(crate)`
+fn vis(trees: &mut Trees) -> TokenStream {
+    let mut vis = TokenStream::new();
+    if matches!(trees.peek(), Some(TokenTree::Ident(ident)) if ident == "pub") {
+        vis.extend(trees.next());
+        if matches!(
+            trees.peek(),
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis
+        ) {
+            vis.extend(trees.next());
+        }
+    }
+    vis
+}
+
+/// Collect the tokens of a type up to the `=` which follows it
+fn until_eq(trees: &mut Trees) -> TokenStream {
+    let mut ty = TokenStream::new();
+    let mut depth = 0usize;
+    let mut arrow = false;
+    while let Some(tree) = trees.peek() {
+        if is_punct(tree, '=') && depth == 0 && !arrow {
+            break;
+        }
+        if is_punct(tree, '<') {
+            depth += 1;
+        } else if is_punct(tree, '>') && !arrow {
+            depth = depth.saturating_sub(1);
+        }
+        arrow = is_punct(tree, '-');
+        ty.extend(trees.next());
+    }
+    ty
+}
+
+/// Expect a particular keyword, or report `message`
+fn expect_keyword(trees: &mut Trees, keyword: &str, message: &str) -> Result<(), Error> {
+    match trees.next() {
+        Some(TokenTree::Ident(ident)) if ident == keyword => Ok(()),
+        Some(tree) => Err(Error::new(tree.span(), message)),
+        None => Err(Error::new(Span::call_site(), message)),
+    }
+}
+
+/// Expect an identifier, or report `message`
+fn expect_ident(trees: &mut Trees, message: &str) -> Result<Ident, Error> {
+    match trees.next() {
+        Some(TokenTree::Ident(ident)) => Ok(ident),
+        Some(tree) => Err(Error::new(tree.span(), message)),
+        None => Err(Error::new(Span::call_site(), message)),
+    }
+}
+
+/// Expect a punctuation character, or report `message`
+fn expect_punct(trees: &mut Trees, c: char, message: &str) -> Result<(), Error> {
+    match trees.next() {
+        Some(tree) if is_punct(&tree, c) => Ok(()),
+        Some(tree) => Err(Error::new(tree.span(), message)),
+        None => Err(Error::new(Span::call_site(), message)),
+    }
+}
+
+/// Returns whether a token is the given punctuation character
+fn is_punct(tree: &TokenTree, c: char) -> bool {
+    matches!(tree, TokenTree::Punct(punct) if punct.as_char() == c)
+}
+
+/// Returns whether an identifier names a built-in delimited group, such as `paren(...)`
+fn is_delimiter(ident: &Ident) -> bool {
+    ident == "paren" || ident == "brace" || ident == "bracket"
+}
+
+/// Compile an ordered choice between sequences separated by `/`, such as `a b / c`
+fn choice(trees: Vec<TokenTree>, rules: &[&Ident]) -> Result<TokenStream, Error> {
+    let parser = parser_trait();
+    let mut alternatives = vec![Vec::new()];
+    for tree in trees {
+        if is_punct(&tree, '/') {
+            alternatives.push(Vec::new());
+        } else if let Some(last) = alternatives.last_mut() {
+            last.push(tree);
+        }
+    }
+
+    let mut alternatives = alternatives
+        .into_iter()
+        .map(|alternative| sequence(alternative, rules));
+    let first = alternatives.next().unwrap_or_else(|| Ok(empty()))?;
+    alternatives.try_fold(first, |choice, next| {
+        let next = next?;
+        Ok(quote!(#parser::or(#choice, #next)))
+    })
+}
+
+/// A parser which accepts nothing
+fn empty() -> TokenStream {
+    quote!(::chumsky_proc::__private::chumsky::prelude::empty())
+}
+
+/// Compile a sequence of items, optionally bound to names and followed by an action block, such
+/// as `l:term "+" r:term { l + r }`
+fn sequence(trees: Vec<TokenTree>, rules: &[&Ident]) -> Result<TokenStream, Error> {
+    let parser = parser_trait();
+    let mut trees = trees.into_iter().peekable();
+    let mut items = Vec::new();
+    let mut action = None;
+
+    while let Some(tree) = trees.peek() {
+        if let TokenTree::Group(group) = tree {
+            if group.delimiter() == Delimiter::Brace {
+                let group = group.clone();
+                trees.next();
+                if let Some(tree) = trees.next() {
+                    return Err(Error::new(
+                        tree.span(),
+                        "an action block must end its sequence",
+                    ));
+                }
+                action = Some(group.stream());
+                break;
+            }
+        }
+
+        let mut ahead = trees.clone();
+        let binding = match (ahead.next(), ahead.next()) {
+            (Some(TokenTree::Ident(name)), Some(TokenTree::Punct(colon)))
+                if colon.as_char() == ':' && colon.spacing() == Spacing::Alone =>
+            {
+                trees.next();
+                trees.next();
+                Some(name)
+            }
+            _ => None,
+        };
+        items.push((binding, item(&mut trees, rules)?));
+    }
+
+    if items.is_empty() && action.is_none() {
+        return Err(Error::new(Span::call_site(), "expected something to match"));
+    }
+
+    let names = items
+        .iter()
+        .filter_map(|(binding, _)| binding.clone())
+        .collect::<Vec<_>>();
+    for (idx, name) in names.iter().enumerate() {
+        if names[..idx].contains(name) {
+            return Err(Error::new(
+                name.span(),
+                format!("`{}` is bound more than once in this sequence", name),
+            ));
+        }
+    }
+
+    // A lone item without an action passes its output through
+    if action.is_none() && names.is_empty() && items.len() == 1 {
+        return Ok(items.remove(0).1);
+    }
+
+    let mut items = items.into_iter().map(|(binding, parser)| {
+        let pattern = match binding {
+            Some(name) => quote!(#name),
+            None => quote!(_),
+        };
+        (parser, pattern)
+    });
+    let (seq, pattern) = match items.next() {
+        Some(first) => items.fold(first, |(seq, pattern), (next, next_pattern)| {
+            (
+                quote!(#parser::then(#seq, #next)),
+                quote!((#pattern, #next_pattern)),
+            )
+        }),
+        None => (empty(), quote!(())),
+    };
+
+    let value = match (action, names.as_slice()) {
+        (Some(action), _) => quote!({ #action }),
+        (None, []) => quote!(()),
+        (None, [name]) => quote!(#name),
+        (None, names) => quote!((#(#names),*)),
+    };
+    Ok(quote!(#parser::map(#seq, |#pattern| #value)))
+}
+
+/// Compile a single item, with any `&` lookahead before it, and any repetition operator or
+/// `@ "label"` after it
+fn item(trees: &mut Trees, rules: &[&Ident]) -> Result<TokenStream, Error> {
+    let parser = parser_trait();
+    if trees.peek().is_some_and(|tree| is_punct(tree, '&')) {
+        trees.next();
+        let item = item(trees, rules)?;
+        return Ok(quote!(#parser::rewind(#item)));
+    }
+
+    let mut item = primary(trees, rules)?;
+    loop {
+        match trees.peek() {
+            Some(tree) if is_punct(tree, '*') => {
+                item = quote!(#parser::repeated(#item));
+            }
+            Some(tree) if is_punct(tree, '+') => {
+                item = quote!(#parser::repeated(#item).at_least(1));
+            }
+            Some(tree) if is_punct(tree, '?') => {
+                item = quote!(#parser::or_not(#item));
+            }
+            Some(tree) if is_punct(tree, '@') => {
+                let span = tree.span();
+                trees.next();
+                match trees.peek() {
+                    Some(TokenTree::Literal(label)) if label.to_string().starts_with('"') => {
+                        item = quote!(#parser::labelled(#item, #label));
+                    }
+                    _ => {
+                        return Err(Error::new(
+                            span,
+                            "expected a label after `@`, like `@ \"an expression\"`",
+                        ))
+                    }
+                }
+            }
+            _ => break,
+        }
+        trees.next();
+    }
+    Ok(item)
+}
+
+/// Compile a terminal, rule reference, or parenthesized choice
+fn primary(trees: &mut Trees, rules: &[&Ident]) -> Result<TokenStream, Error> {
+    let Some(tree) = trees.next() else {
+        return Err(Error::new(Span::call_site(), "expected something to match"));
+    };
+    match tree {
+        TokenTree::Literal(lit) => terminal(&lit),
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {
+            choice(group.stream().into_iter().collect(), rules)
+        }
+        TokenTree::Punct(pound) if pound.as_char() == '#' => match trees.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                Ok(group.stream())
+            }
+            _ => Err(Error::new(
+                pound.span(),
+                "expected a parser in parentheses after `#`, like `#(my_parser())`",
+            )),
+        },
+        TokenTree::Ident(ident) if is_delimiter(&ident) => {
+            let delimiter = match &*ident.to_string() {
+                "paren" => quote!(Parenthesis),
+                "brace" => quote!(Brace),
+                _ => quote!(Bracket),
+            };
+            let inner = match trees.next() {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                    choice(group.stream().into_iter().collect(), rules)?
+                }
+                _ => {
+                    return Err(Error::new(
+                        ident.span(),
+                        format!(
+                            "expected the group's contents in parentheses, like `{}(...)`",
+                            ident
+                        ),
+                    ))
+                }
+            };
+            Ok(quote_spanned! {ident.span()=>
+                ::chumsky_proc::primitive::group(
+                    ::chumsky_proc::__private::proc_macro2::Delimiter::#delimiter,
+                    #inner,
+                )
+            })
+        }
+        TokenTree::Ident(ident) if rules.contains(&&ident) => {
+            Ok(quote!(::core::clone::Clone::clone(&#ident)))
+        }
+        TokenTree::Ident(ident) => builtin(&ident).ok_or_else(|| {
+            Error::new(
+                ident.span(),
+                format!(
+                    "unknown rule `{}`, expected a rule of this grammar or one of {}",
+                    ident,
+                    KINDS.join(", "),
+                ),
+            )
+        }),
+        tree => Err(Error::new(tree.span(), "expected something to match")),
+    }
+}
+
+/// Compile a string literal terminal, which is either a keyword such as `"let"` or punctuation
+/// such as `"+="`
+fn terminal(lit: &Literal) -> Result<TokenStream, Error> {
+    let text = lit.to_string();
+    let Some(text) = text
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .filter(|text| !text.is_empty() && !text.contains('\\'))
+    else {
+        return Err(Error::new(
+            lit.span(),
+            "expected a keyword or punctuation in a string, like \"let\" or \"+=\"",
+        ));
+    };
+
+    let span = lit.span();
+    if is_ident(text) {
+        return Ok(quote_spanned!(span=> ::chumsky_proc::primitive::keyword(#text)));
+    }
+    if !text.chars().all(|c| PUNCT_CHARS.contains(c)) {
+        return Err(Error::new(
+            span,
+            format!("`{}` isn't a keyword or punctuation", text),
+        ));
+    }
+
+    let mut chars = text.chars();
+    Ok(match (chars.next(), chars.next()) {
+        (Some(c), None) => quote_spanned!(span=> ::chumsky_proc::primitive::punct(#c)),
+        _ => quote_spanned! {span=>
+            ::chumsky_proc::__private::chumsky::Parser::ignored(
+                ::chumsky_proc::primitive::joined_punct(#text),
+            )
+        },
+    })
+}
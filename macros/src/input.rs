@@ -0,0 +1,356 @@
+//! A minimal parser for the items given to derive macros
+
+use proc_macro2::{Delimiter, Ident, Span, TokenStream, TokenTree};
+use std::iter::Peekable;
+
+use crate::error::Error;
+
+/// The token iterator used while parsing
+type Trees = Peekable<proc_macro2::token_stream::IntoIter>;
+
+/// An outer attribute, such as `#[keyword = "kg"]`
+pub(crate) struct Attr {
+    /// The first segment of the attribute's path, such as `keyword`
+    pub name: Ident,
+    /// The tokens following the path, such as `= "kg"` or `("let")`
+    pub args: TokenStream,
+    /// The span of the whole attribute
+    pub span: Span,
+}
+
+/// The fields of a struct or enum variant
+pub(crate) enum Fields {
+    /// Named fields, such as `{ a: u8 }`
+    Named(Vec<Field>),
+    /// Unnamed fields, such as `(u8, u8)`
+    Unnamed(Vec<Field>),
+    /// No fields at all
+    Unit,
+}
+
+impl Fields {
+    /// Iterate over the fields
+    pub(crate) fn iter(&self) -> std::slice::Iter<'_, Field> {
+        match self {
+            Fields::Named(fields) | Fields::Unnamed(fields) => fields.iter(),
+            Fields::Unit => [].iter(),
+        }
+    }
+}
+
+/// A single field of a struct or enum variant
+pub(crate) struct Field {
+    /// The attributes on the field
+    pub attrs: Vec<Attr>,
+    /// The name of the field, if it's named
+    pub ident: Option<Ident>,
+    /// The type of the field
+    pub ty: TokenStream,
+    /// The span of the field's first token
+    pub span: Span,
+}
+
+/// A single enum variant
+pub(crate) struct Variant {
+    /// The attributes on the variant
+    pub attrs: Vec<Attr>,
+    /// The name of the variant
+    pub ident: Ident,
+    /// The fields of the variant
+    pub fields: Fields,
+}
+
+/// The body of the item being derived for
+pub(crate) enum Data {
+    /// A struct, with its fields
+    Struct(Fields),
+    /// An enum, with its variants
+    Enum(Vec<Variant>),
+}
+
+/// The item a derive macro was applied to
+pub(crate) struct DeriveInput {
+    /// The outer attributes of the item
+    pub attrs: Vec<Attr>,
+    /// The name of the item
+    pub ident: Ident,
+    /// The generic parameters, without the angle brackets, such as `'a` and `T: Clone`
+    pub generics: Vec<TokenStream>,
+    /// The body of the item
+    pub data: Data,
+}
+
+impl DeriveInput {
+    /// Parse the item given to a derive macro
+    pub(crate) fn parse(input: TokenStream) -> Result<DeriveInput, Error> {
+        let mut trees = input.into_iter().peekable();
+        let attrs = attrs(&mut trees);
+        skip_vis(&mut trees);
+
+        let kind = expect_ident(&mut trees, "expected `struct`, `enum` or `union`")?;
+        let ident = expect_ident(&mut trees, "expected a name")?;
+        let generics = generics(&mut trees);
+        skip_where_clause(&mut trees);
+
+        let data = match &*kind.to_string() {
+            "struct" => Data::Struct(match trees.next() {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                    Fields::Named(fields(group.stream(), true)?)
+                }
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                    Fields::Unnamed(fields(group.stream(), false)?)
+                }
+                _ => Fields::Unit,
+            }),
+            "enum" => {
+                let body = match trees.next() {
+                    Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                        group.stream()
+                    }
+                    _ => return Err(Error::new(ident.span(), "expected the enum's variants")),
+                };
+                // Only discriminant expressions, such as `1 << 2`, contain `<` and `>` between
+                // the variants, so they aren't angle brackets
+                let variants = split_commas(body, false)
+                    .into_iter()
+                    .map(variant)
+                    .collect::<Result<_, _>>()?;
+                Data::Enum(variants)
+            }
+            _ => {
+                return Err(Error::new(
+                    kind.span(),
+                    "this derive only supports structs and enums",
+                ))
+            }
+        };
+
+        Ok(DeriveInput {
+            attrs,
+            ident,
+            generics,
+            data,
+        })
+    }
+}
+
+/// Parse any outer attributes
+fn attrs(trees: &mut Trees) -> Vec<Attr> {
+    let mut attrs = Vec::new();
+    while matches!(trees.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '#') {
+        let Some(pound) = trees.next() else {
+            break;
+        };
+        let Some(TokenTree::Group(group)) = trees.next() else {
+            break;
+        };
+        let mut inner = group.stream().into_iter();
+        if let Some(TokenTree::Ident(name)) = inner.next() {
+            attrs.push(Attr {
+                name,
+                args: inner.collect(),
+                span: pound.span(),
+            });
+        }
+    }
+    attrs
+}
+
+/// Skip a visibility, such as `pub` or `pub(crate)`
+fn skip_vis(trees: &mut Trees) {
+    if matches!(trees.peek(), Some(TokenTree::Ident(ident)) if ident == "pub") {
+        trees.next();
+        if matches!(
+            trees.peek(),
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis
+        ) {
+            trees.next();
+        }
+    }
+}
+
+/// Expect an identifier, or report `message`
+fn expect_ident(trees: &mut Trees, message: &str) -> Result<Ident, Error> {
+    match trees.next() {
+        Some(TokenTree::Ident(ident)) => Ok(ident),
+        Some(tree) => Err(Error::new(tree.span(), message)),
+        None => Err(Error::new(Span::call_site(), message)),
+    }
+}
+
+/// Returns whether a token is the given punctuation character
+pub(crate) fn is_punct(tree: &TokenTree, c: char) -> bool {
+    matches!(tree, TokenTree::Punct(punct) if punct.as_char() == c)
+}
+
+/// Parse the generic parameters of an item, if there are any
+fn generics(trees: &mut Trees) -> Vec<TokenStream> {
+    if !trees.peek().is_some_and(|tree| is_punct(tree, '<')) {
+        return Vec::new();
+    }
+    trees.next();
+
+    let mut depth = 0usize;
+    let mut inner = TokenStream::new();
+    let mut arrow = false;
+    for tree in trees.by_ref() {
+        // The `>` of an arrow, such as in `F: Fn() -> u8`, isn't a closing bracket
+        if is_punct(&tree, '<') {
+            depth += 1;
+        } else if is_punct(&tree, '>') && !arrow {
+            if depth == 0 {
+                break;
+            }
+            depth -= 1;
+        }
+        arrow = is_punct(&tree, '-');
+        inner.extend([tree]);
+    }
+
+    split_commas(inner, true)
+}
+
+/// Skip a where clause, if there is one
+fn skip_where_clause(trees: &mut Trees) {
+    if !matches!(trees.peek(), Some(TokenTree::Ident(ident)) if ident == "where") {
+        return;
+    }
+    while let Some(tree) = trees.peek() {
+        let is_body =
+            matches!(tree, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace);
+        if is_body || is_punct(tree, ';') {
+            break;
+        }
+        trees.next();
+    }
+}
+
+/// Split a token stream at commas, dropping empty parts. With `angle_brackets`, commas nested in
+/// angle brackets are skipped, as in types and generics.
+fn split_commas(stream: TokenStream, angle_brackets: bool) -> Vec<TokenStream> {
+    let mut parts = Vec::new();
+    let mut current = TokenStream::new();
+    let mut depth = 0usize;
+    let mut arrow = false;
+
+    for tree in stream {
+        if is_punct(&tree, ',') && depth == 0 {
+            parts.push(std::mem::take(&mut current));
+            arrow = false;
+            continue;
+        } else if angle_brackets && is_punct(&tree, '<') {
+            depth += 1;
+        } else if angle_brackets && is_punct(&tree, '>') && !arrow {
+            depth = depth.saturating_sub(1);
+        }
+        arrow = is_punct(&tree, '-') || is_punct(&tree, '=');
+        current.extend([tree]);
+    }
+    parts.push(current);
+
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// Parse the fields inside the body of a struct or variant
+fn fields(stream: TokenStream, named: bool) -> Result<Vec<Field>, Error> {
+    split_commas(stream, true)
+        .into_iter()
+        .map(|field| {
+            let mut trees = field.into_iter().peekable();
+            let span = trees.peek().map_or_else(Span::call_site, TokenTree::span);
+            let attrs = attrs(&mut trees);
+            skip_vis(&mut trees);
+            let ident = if named {
+                let ident = expect_ident(&mut trees, "expected a field name")?;
+                if !trees.next().is_some_and(|tree| is_punct(&tree, ':')) {
+                    return Err(Error::new(
+                        ident.span(),
+                        "expected `:` after the field name",
+                    ));
+                }
+                Some(ident)
+            } else {
+                None
+            };
+            Ok(Field {
+                attrs,
+                ident,
+                ty: trees.collect(),
+                span,
+            })
+        })
+        .collect()
+}
+
+/// Parse a single enum variant
+fn variant(stream: TokenStream) -> Result<Variant, Error> {
+    let mut trees = stream.into_iter().peekable();
+    let attrs = attrs(&mut trees);
+    let ident = expect_ident(&mut trees, "expected a variant name")?;
+    let fields = match trees.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            Fields::Named(fields(group.stream(), true)?)
+        }
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            Fields::Unnamed(fields(group.stream(), false)?)
+        }
+        _ => Fields::Unit,
+    };
+    Ok(Variant {
+        attrs,
+        ident,
+        fields,
+    })
+}
+
+/// Get the single string literal argument of an attribute written like `#[name = "value"]` or
+/// `#[name("value")]`
+pub(crate) fn str_arg(attr: &Attr) -> Result<String, Error> {
+    let mut trees = attr.args.clone().into_iter();
+    let lit = match (trees.next(), trees.next(), trees.next()) {
+        (Some(eq), Some(TokenTree::Literal(lit)), None) if is_punct(&eq, '=') => lit,
+        (Some(TokenTree::Group(group)), None, None)
+            if group.delimiter() == Delimiter::Parenthesis =>
+        {
+            let mut inner = group.stream().into_iter();
+            match (inner.next(), inner.next()) {
+                (Some(TokenTree::Literal(lit)), None) => lit,
+                _ => return Err(expected_str(attr)),
+            }
+        }
+        _ => return Err(expected_str(attr)),
+    };
+
+    let text = lit.to_string();
+    text.strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .filter(|text| !text.contains('\\'))
+        .map(str::to_string)
+        .ok_or_else(|| expected_str(attr))
+}
+
+/// Report an attribute which should have had a single string argument
+fn expected_str(attr: &Attr) -> Error {
+    Error::new(
+        attr.span,
+        format!(
+            "expected a string, like `#[{} = \"...\"]` or `#[{}(\"...\")]`",
+            attr.name, attr.name
+        ),
+    )
+}
+
+/// Get the tokens inside the parentheses of an attribute written like `#[name(...)]`
+pub(crate) fn paren_args(attr: &Attr) -> Result<TokenStream, Error> {
+    let mut trees = attr.args.clone().into_iter();
+    match (trees.next(), trees.next()) {
+        (Some(TokenTree::Group(group)), None) if group.delimiter() == Delimiter::Parenthesis => {
+            Ok(group.stream())
+        }
+        _ => Err(Error::new(
+            attr.span,
+            format!("expected arguments, like `#[{}(...)]`", attr.name),
+        )),
+    }
+}
@@ -0,0 +1,110 @@
+//! The `Keyword` derive, turning an enum of unit variants into a keyword set
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
+
+use crate::error::Error;
+use crate::input::{str_arg, Data, DeriveInput, Fields};
+
+/// Convert a variant name to snake case, such as `MetersPerSecond` to `meters_per_second`
+fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len());
+    for (idx, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if idx > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Returns whether some text is a single plain identifier, and so usable as a keyword
+pub(crate) fn is_ident(text: &str) -> bool {
+    let Ok(stream) = text.parse::<TokenStream>() else {
+        return false;
+    };
+    let mut trees = stream.into_iter();
+    match (trees.next(), trees.next()) {
+        (Some(TokenTree::Ident(ident)), None) => ident == text && !text.starts_with("r#"),
+        _ => false,
+    }
+}
+
+/// Expand a `#[derive(Keyword)]`
+pub(crate) fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+    let input = DeriveInput::parse(input)?;
+    let name = &input.ident;
+
+    let Data::Enum(variants) = input.data else {
+        return Err(Error::new(
+            name.span(),
+            "`Keyword` can only be derived for enums",
+        ));
+    };
+    if !input.generics.is_empty() {
+        return Err(Error::new(
+            name.span(),
+            "`Keyword` can't be derived for generic enums",
+        ));
+    }
+    if variants.is_empty() {
+        return Err(Error::new(
+            name.span(),
+            "`Keyword` can't be derived for enums without variants",
+        ));
+    }
+
+    let mut keywords = Vec::<String>::with_capacity(variants.len());
+    let mut idents = Vec::with_capacity(variants.len());
+    for variant in &variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(
+                variant.ident.span(),
+                "`Keyword` variants can't have fields",
+            ));
+        }
+
+        let (keyword, span) = match variant.attrs.iter().find(|attr| attr.name == "keyword") {
+            Some(attr) => (str_arg(attr)?, attr.span),
+            None => (snake_case(&variant.ident.to_string()), variant.ident.span()),
+        };
+        if !is_ident(&keyword) {
+            return Err(Error::new(
+                span,
+                format!(
+                    "`{}` isn't a valid identifier, so can't be a keyword",
+                    keyword
+                ),
+            ));
+        }
+        if keywords.contains(&keyword) {
+            return Err(Error::new(span, format!("duplicate keyword `{}`", keyword)));
+        }
+
+        keywords.push(keyword);
+        idents.push(&variant.ident);
+    }
+
+    Ok(quote! {
+        impl ::chumsky_proc::primitive::Keyword for #name {
+            const KEYWORDS: &'static [&'static str] = &[#(#keywords),*];
+
+            fn from_keyword(keyword: &str) -> ::core::option::Option<Self> {
+                match keyword {
+                    #(#keywords => ::core::option::Option::Some(#name::#idents),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+
+            fn as_keyword(&self) -> &'static str {
+                match self {
+                    #(#name::#idents => #keywords,)*
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,173 @@
+//! Procedural macros for chumsky-proc
+//!
+//! These are re-exported from `chumsky_proc` when its `macros` feature is enabled, which it is
+//! by default, so use them from there rather than depending on this crate directly.
+
+#![warn(
+    missing_docs,
+    elided_lifetimes_in_paths,
+    explicit_outlives_requirements,
+    missing_abi,
+    noop_method_call,
+    semicolon_in_expressions_from_macros,
+    unused_import_braces,
+    unused_lifetimes,
+    clippy::missing_docs_in_private_items,
+    clippy::missing_panics_doc,
+    clippy::doc_markdown,
+    clippy::ptr_as_ptr,
+    clippy::cloned_instead_of_copied,
+    clippy::unreadable_literal,
+    clippy::map_unwrap_or,
+    clippy::match_same_arms,
+    clippy::redundant_closure,
+    clippy::redundant_closure_call,
+    clippy::redundant_closure_for_method_calls
+)]
+
+use proc_macro::TokenStream;
+
+mod error;
+mod grammar;
+mod input;
+mod keyword;
+mod parse;
+mod tokens;
+
+/// Build a parser from a quote-like pattern of tokens
+///
+/// Identifiers become [`keyword`] matchers, punctuation becomes [`punct`] or, when joined like
+/// `+=`, [`joined_punct`] matchers, literals are matched by their text, and delimited groups are
+/// matched with [`group`]. Placeholders like `#name:kind` capture the output of a parser, where
+/// the kind is one of `attrs`, `block`, `expr`, `generics`, `ident`, `item`, `lifetime`,
+/// `literal`, `meta`, `pat`, `path`, `punct`, `tt`, `ty` or `vis`, or any parser expression in
+/// parentheses. A placeholder named `_` is matched but not captured.
+///
+/// The parser outputs `()` with no captures, the capture itself with one, or a tuple of all
+/// captures in order. Ending the pattern with `=> Path` instead builds that struct, with a field
+/// named after each capture.
+///
+/// [`keyword`]: https://docs.rs/chumsky-proc/latest/chumsky_proc/primitive/fn.keyword.html
+/// [`punct`]: https://docs.rs/chumsky-proc/latest/chumsky_proc/primitive/fn.punct.html
+/// [`joined_punct`]: https://docs.rs/chumsky-proc/latest/chumsky_proc/primitive/fn.joined_punct.html
+/// [`group`]: https://docs.rs/chumsky-proc/latest/chumsky_proc/primitive/fn.group.html
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::Type;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use proc_macro2::Ident;
+/// # use quote::quote;
+/// let parser = rust_tokens![struct #name:ident { #fields:(ident().then_ignore(punct(',')).repeated()) }]
+///     .then_ignore(end::<Cheap<_, RustSpan>>());
+///
+/// let (name, fields) = parser
+///     .parse(stream_from_tokens(quote!(struct Foo { a, b, })))
+///     .unwrap();
+/// assert_eq!(name, "Foo");
+/// assert_eq!(fields.len(), 2);
+///
+/// parser.parse(stream_from_tokens(quote!(enum Foo {}))).unwrap_err();
+///
+/// struct Field {
+///     name: Ident,
+///     ty: Type,
+/// }
+///
+/// let parser = rust_tokens![#name:ident : #ty:ty => Field].then_ignore(end::<Cheap<_, RustSpan>>());
+/// let field = parser.parse(stream_from_tokens(quote!(a: Vec<u8>))).unwrap();
+/// assert_eq!(field.name, "a");
+/// ```
+#[proc_macro]
+pub fn rust_tokens(input: TokenStream) -> TokenStream {
+    tokens::expand(input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Build a set of mutually recursive parsers from PEG-style rules
+///
+/// The grammar starts with its name, like `pub struct Calc;`, which becomes a struct with a
+/// `new()` constructor and a `BoxedParser` field for each public rule. Each rule is written like
+/// `pub rule name -> Output = ...;`, and outputs `()` when no output type is given. Rules can
+/// refer to each other, and themselves, in any order, and a rule shadows any built-in terminal
+/// with the same name.
+///
+/// Inside a rule:
+///
+/// - `"let"` matches a keyword, and `"+"` or `"+="` matches punctuation
+/// - `ident`, `literal`, `ty` and the other placeholder kinds of `rust_tokens!` match with their
+///   built-in parsers
+/// - `paren(...)`, `brace(...)` and `bracket(...)` match inside a delimited group
+/// - `#(parser)` matches with any parser expression
+/// - `a b` matches in sequence, and `a / b` tries each alternative in order
+/// - `a*`, `a+` and `a?` repeat or make optional, and `&a` checks for `a` without consuming it
+/// - `(...)` groups, and `name:a` binds the output of `a` for the sequence's action
+/// - `{ ... }` at the end of a sequence is an action, building its output from the bindings
+/// - `a @ "label"` or `#[label = "..."]` on a rule labels errors from it
+///
+/// Without an action, a sequence outputs its bindings - nothing, a single value, or a tuple - or
+/// the output of its only item.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Simple;
+/// # use quote::quote;
+/// grammar! {
+///     /// Integer arithmetic
+///     struct Calc;
+///
+///     #[label = "an expression"]
+///     pub rule expr -> i64 =
+///         first:term rest:("+" t:term { t } / "-" t:term { -t })* {
+///             first + rest.iter().sum::<i64>()
+///         };
+///     rule term -> i64 = first:atom rest:("*" a:atom)* { first * rest.iter().product::<i64>() };
+///     rule atom -> i64 =
+///         lit:literal { lit.to_string().parse().unwrap() }
+///         / paren(expr)
+///         / "neg" a:atom { -a };
+/// }
+///
+/// let parser = Calc::<Simple<_, RustSpan>>::new().expr.then_ignore(end());
+/// let value = parser.parse(stream_from_tokens(quote!(1 + 2 * (3 - neg 1)))).unwrap();
+/// assert_eq!(value, 9);
+///
+/// let errs = parser.parse(stream_from_tokens(quote!(1 + ()))).unwrap_err();
+/// assert_eq!(errs[0].label(), Some("an expression"));
+/// ```
+#[proc_macro]
+pub fn grammar(input: TokenStream) -> TokenStream {
+    grammar::expand(input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Implement `Keyword` for an enum of unit variants, making each variant a keyword. Keywords are
+/// the snake-cased variant names, unless given with `#[keyword = "..."]`.
+///
+/// See the `Keyword` trait for an example.
+#[proc_macro_derive(Keyword, attributes(keyword))]
+pub fn derive_keyword(input: TokenStream) -> TokenStream {
+    keyword::expand(input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Implement `Parse` for a struct or enum, parsing each of its fields in order. Fields, structs
+/// and variants can be annotated with `#[keyword("...")]`, `#[punct('...')]`,
+/// `#[delimited(...)]`, `#[separated('...')]`, `#[peek]` and `#[parse(with = ...)]`.
+///
+/// See the `Parse` trait for details and an example.
+#[proc_macro_derive(Parse, attributes(keyword, punct, delimited, separated, peek, parse))]
+pub fn derive_parse(input: TokenStream) -> TokenStream {
+    parse::expand(input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
@@ -0,0 +1,475 @@
+//! The `Parse` derive, building a parser from the fields of a struct or enum
+
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned};
+
+use crate::error::Error;
+use crate::input::{is_punct, paren_args, str_arg, Attr, Data, DeriveInput, Field, Fields};
+use crate::keyword::is_ident;
+
+/// The characters which can be used as punctuation
+pub(crate) const PUNCT_CHARS: &str = "!#$%&*+,-./:;<=>?@^|~";
+
+/// The parsing attributes on a field, struct or enum variant
+#[derive(Default)]
+struct Attrs {
+    /// The parsers for the keywords and punctuation expected first, in order
+    prefix: Vec<TokenStream>,
+    /// The delimiter of the group to parse inside, from `#[delimited(...)]`
+    delimiter: Option<(TokenStream, Span)>,
+    /// The separator between `Vec` elements, from `#[separated(...)]`
+    separator: Option<(TokenStream, Span)>,
+    /// The span of `#[peek]`, if it was given
+    peek: Option<Span>,
+    /// The parser given with `#[parse(with = ...)]`
+    with: Option<(TokenStream, Span)>,
+}
+
+impl Attrs {
+    /// Collect the parsing attributes, ignoring any others such as doc comments
+    fn parse(attrs: &[Attr]) -> Result<Attrs, Error> {
+        let mut parsed = Attrs::default();
+        for attr in attrs {
+            match &*attr.name.to_string() {
+                "keyword" => {
+                    let keyword = str_arg(attr)?;
+                    if !is_ident(&keyword) {
+                        return Err(Error::new(
+                            attr.span,
+                            format!(
+                                "`{}` isn't a valid identifier, so can't be a keyword",
+                                keyword
+                            ),
+                        ));
+                    }
+                    parsed.prefix.push(
+                        quote_spanned!(attr.span=> ::chumsky_proc::primitive::keyword(#keyword)),
+                    );
+                }
+                "punct" => parsed
+                    .prefix
+                    .push(punct_parser(&punct_arg(attr)?, attr.span)),
+                "delimited" => parsed.delimiter = Some((delimiter_arg(attr)?, attr.span)),
+                "separated" => {
+                    parsed.separator =
+                        Some((punct_parser(&punct_arg(attr)?, attr.span), attr.span));
+                }
+                "peek" => {
+                    if !attr.args.is_empty() {
+                        return Err(Error::new(attr.span, "`#[peek]` doesn't take arguments"));
+                    }
+                    parsed.peek = Some(attr.span);
+                }
+                "parse" => parsed.with = Some((with_arg(attr)?, attr.span)),
+                _ => (),
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Report any attributes which can only be used on fields
+    fn only_prefix(&self) -> Result<(), Error> {
+        let field_only = [
+            (&self.delimiter, "delimited"),
+            (&self.separator, "separated"),
+            (&self.with, "parse"),
+        ];
+        for (attr, name) in field_only {
+            if let Some((_, span)) = attr {
+                return Err(Error::new(
+                    *span,
+                    format!("`#[{}]` can only be used on fields", name),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Get the punctuation of an attribute written like `#[punct(':')]` or `#[punct("::")]`
+fn punct_arg(attr: &Attr) -> Result<String, Error> {
+    let expected = || {
+        Error::new(
+            attr.span,
+            format!(
+                "expected punctuation, like `#[{}(',')]` or `#[{}(\"::\")]`",
+                attr.name, attr.name
+            ),
+        )
+    };
+
+    let mut trees = paren_args(attr)?.into_iter();
+    let Some(TokenTree::Literal(lit)) = trees.next() else {
+        return Err(expected());
+    };
+    if trees.next().is_some() {
+        return Err(expected());
+    }
+
+    let text = lit.to_string();
+    let text = text
+        .strip_prefix('\'')
+        .and_then(|text| text.strip_suffix('\''))
+        .or_else(|| {
+            text.strip_prefix('"')
+                .and_then(|text| text.strip_suffix('"'))
+        })
+        .ok_or_else(expected)?;
+    if text.is_empty() || !text.chars().all(|c| PUNCT_CHARS.contains(c)) {
+        return Err(expected());
+    }
+    Ok(text.to_string())
+}
+
+/// Get the delimiter of an attribute written like `#[delimited(paren)]`
+fn delimiter_arg(attr: &Attr) -> Result<TokenStream, Error> {
+    let mut trees = paren_args(attr)?.into_iter();
+    let delimiter = match (trees.next(), trees.next()) {
+        (Some(TokenTree::Ident(name)), None) => match &*name.to_string() {
+            "paren" => Some(quote!(Parenthesis)),
+            "brace" => Some(quote!(Brace)),
+            "bracket" => Some(quote!(Bracket)),
+            _ => None,
+        },
+        _ => None,
+    };
+    let delimiter = delimiter.ok_or_else(|| {
+        Error::new(
+            attr.span,
+            "expected `#[delimited(paren)]`, `#[delimited(brace)]` or `#[delimited(bracket)]`",
+        )
+    })?;
+    Ok(quote!(::chumsky_proc::__private::proc_macro2::Delimiter::#delimiter))
+}
+
+/// Get the parser of an attribute written like `#[parse(with = parser())]`
+fn with_arg(attr: &Attr) -> Result<TokenStream, Error> {
+    let mut trees = paren_args(attr)?.into_iter();
+    match (trees.next(), trees.next()) {
+        (Some(TokenTree::Ident(with)), Some(eq)) if with == "with" && is_punct(&eq, '=') => {
+            let parser = trees.collect::<TokenStream>();
+            if !parser.is_empty() {
+                return Ok(parser);
+            }
+        }
+        _ => (),
+    }
+    Err(Error::new(
+        attr.span,
+        "expected a parser, like `#[parse(with = parser())]`",
+    ))
+}
+
+/// Build the parser for some punctuation, outputting `()`
+fn punct_parser(text: &str, span: Span) -> TokenStream {
+    if text.chars().count() == 1 {
+        let c = text.chars().next();
+        quote_spanned!(span=> ::chumsky_proc::primitive::punct(#c))
+    } else {
+        quote_spanned! {span=>
+            ::chumsky_proc::__private::chumsky::Parser::ignored(
+                ::chumsky_proc::primitive::joined_punct(#text),
+            )
+        }
+    }
+}
+
+/// Split a type such as `Vec<T>` into the name of its wrapper and the wrapped type, if it's a
+/// `Box`, `Option` or `Vec`
+fn wrapper(ty: &TokenStream) -> Option<(String, TokenStream)> {
+    let trees = ty.clone().into_iter().collect::<Vec<_>>();
+    let open = trees.iter().position(|tree| is_punct(tree, '<'))?;
+    if !trees.last().is_some_and(|tree| is_punct(tree, '>')) {
+        return None;
+    }
+
+    let path = &trees[..open];
+    let is_path = path
+        .iter()
+        .all(|tree| matches!(tree, TokenTree::Ident(_)) || is_punct(tree, ':'));
+    let Some(TokenTree::Ident(name)) = path.last() else {
+        return None;
+    };
+    let name = name.to_string();
+    if !is_path || !["Box", "Option", "Vec"].contains(&name.as_str()) {
+        return None;
+    }
+
+    Some((
+        name,
+        trees[open + 1..trees.len() - 1].iter().cloned().collect(),
+    ))
+}
+
+/// The item a parser is being derived for
+struct Context {
+    /// The name of the item, which fields may refer to recursively
+    ident: Ident,
+    /// Whether any field refers to the item recursively
+    recursive: bool,
+}
+
+impl Context {
+    /// Build the parser for a type, unwrapping `Box`, `Option` and `Vec`. The separator is used
+    /// for the outermost `Vec`.
+    fn type_parser(
+        &mut self,
+        ty: &TokenStream,
+        separator: &mut Option<(TokenStream, Span)>,
+    ) -> TokenStream {
+        let parser = quote!(::chumsky_proc::__private::chumsky::Parser);
+        match wrapper(ty) {
+            Some((name, inner)) => {
+                let inner = self.type_parser(&inner, separator);
+                match &*name {
+                    "Box" => quote!(#parser::map(#inner, ::std::boxed::Box::new)),
+                    "Option" => quote!(#parser::or_not(#inner)),
+                    _ => match separator.take() {
+                        Some((separator, _)) => {
+                            quote!(#parser::separated_by(#inner, #separator).allow_trailing())
+                        }
+                        None => quote!(#parser::repeated(#inner)),
+                    },
+                }
+            }
+            None => {
+                let mut trees = ty.clone().into_iter();
+                match (trees.next(), trees.next()) {
+                    (Some(TokenTree::Ident(ident)), None)
+                        if ident == "Self" || ident == self.ident =>
+                    {
+                        self.recursive = true;
+                        quote!(::core::clone::Clone::clone(&__self_parser))
+                    }
+                    // Built lazily, in case the type contains this one
+                    _ => quote!(::chumsky_proc::parse::lazy::<#ty, _>()),
+                }
+            }
+        }
+    }
+
+    /// Build the parser for a single field, alongside whether it has `#[peek]`
+    fn field(&mut self, field: &Field) -> Result<(TokenStream, Option<Span>), Error> {
+        let attrs = Attrs::parse(&field.attrs)?;
+        let mut separator = attrs.separator;
+
+        let (mut parser, optional) = match attrs.with {
+            Some((with, _)) => (with, false),
+            None => match wrapper(&field.ty) {
+                Some((name, inner)) if name == "Option" => {
+                    (self.type_parser(&inner, &mut separator), true)
+                }
+                _ => (self.type_parser(&field.ty, &mut separator), false),
+            },
+        };
+        if let Some((_, span)) = separator {
+            return Err(Error::new(
+                span,
+                "`#[separated]` can only be used on `Vec` fields",
+            ));
+        }
+
+        if let Some((delimiter, span)) = attrs.delimiter {
+            parser = quote_spanned!(span=> ::chumsky_proc::primitive::group(#delimiter, #parser));
+        }
+        for prefix in attrs.prefix.iter().rev() {
+            parser =
+                quote!(::chumsky_proc::__private::chumsky::Parser::ignore_then(#prefix, #parser));
+        }
+        if optional {
+            parser = quote!(::chumsky_proc::__private::chumsky::Parser::or_not(#parser));
+        }
+
+        Ok((parser, attrs.peek))
+    }
+
+    /// Build the parser for a struct or variant, constructing it with `path`. Also returns the
+    /// lookahead given by `#[peek]`, if there is one.
+    fn constructor(
+        &mut self,
+        path: TokenStream,
+        attrs: &[Attr],
+        fields: &Fields,
+    ) -> Result<(TokenStream, Option<(TokenStream, Span)>), Error> {
+        let attrs = Attrs::parse(attrs)?;
+        attrs.only_prefix()?;
+
+        let mut elems = attrs
+            .prefix
+            .into_iter()
+            .map(|prefix| (prefix, quote!(_)))
+            .collect::<Vec<_>>();
+        // On a variant, `#[peek]` checks its keywords and punctuation, and its first field
+        let mut peek = match attrs.peek {
+            Some(span) => {
+                let len = elems.len() + usize::from(fields.iter().next().is_some());
+                if len == 0 {
+                    return Err(Error::new(span, "there's nothing in this variant to peek"));
+                }
+                Some((len, span))
+            }
+            None => None,
+        };
+
+        let mut names = Vec::new();
+        for (idx, field) in fields.iter().enumerate() {
+            let (parser, field_peek) = self.field(field)?;
+            if let Some(span) = field_peek {
+                if peek.is_some() {
+                    return Err(Error::new(
+                        span,
+                        "only one `#[peek]` can be used per variant",
+                    ));
+                }
+                peek = Some((elems.len() + 1, span));
+            }
+
+            let name = format_ident!("__field{}", idx, span = field.span);
+            elems.push((parser, quote!(#name)));
+            names.push(name);
+        }
+
+        let value = match fields {
+            Fields::Named(fields) => {
+                let idents = fields.iter().map(|field| &field.ident);
+                quote!(#path { #(#idents: #names),* })
+            }
+            Fields::Unnamed(_) => quote!(#path(#(#names),*)),
+            Fields::Unit => path,
+        };
+
+        let lookahead = peek.map(|(len, span)| {
+            let (parser, _) = sequence(&elems[..len]);
+            (
+                quote! {
+                    ::chumsky_proc::__private::chumsky::Parser::boxed(
+                        ::chumsky_proc::__private::chumsky::Parser::ignored(#parser),
+                    )
+                },
+                span,
+            )
+        });
+        let (parser, pattern) = sequence(&elems);
+        Ok((
+            quote!(::chumsky_proc::__private::chumsky::Parser::map(#parser, |#pattern| #value)),
+            lookahead,
+        ))
+    }
+}
+
+/// Build the parser for a sequence of parsers, alongside the pattern destructuring its output
+fn sequence(elems: &[(TokenStream, TokenStream)]) -> (TokenStream, TokenStream) {
+    let mut elems = elems.iter().cloned();
+    let Some(first) = elems.next() else {
+        return (
+            quote!(::chumsky_proc::__private::chumsky::prelude::empty()),
+            quote!(()),
+        );
+    };
+
+    elems.fold(first, |(parser, pattern), (next, next_pattern)| {
+        (
+            quote!(::chumsky_proc::__private::chumsky::Parser::then(#parser, #next)),
+            quote!((#pattern, #next_pattern)),
+        )
+    })
+}
+
+/// Expand a `#[derive(Parse)]`
+pub(crate) fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+    let input = DeriveInput::parse(input)?;
+    let name = &input.ident;
+    if !input.generics.is_empty() {
+        return Err(Error::new(
+            name.span(),
+            "`Parse` can't be derived for generic types",
+        ));
+    }
+
+    let mut context = Context {
+        ident: name.clone(),
+        recursive: false,
+    };
+
+    let parser = match &input.data {
+        Data::Struct(fields) => {
+            let (parser, peek) = context.constructor(quote!(Self), &input.attrs, fields)?;
+            if let Some((_, span)) = peek {
+                return Err(Error::new(
+                    span,
+                    "`#[peek]` can only be used on enum variants and their fields",
+                ));
+            }
+            parser
+        }
+        Data::Enum(variants) => {
+            let attrs = Attrs::parse(&input.attrs)?;
+            attrs.only_prefix()?;
+            if !attrs.prefix.is_empty() || attrs.peek.is_some() {
+                return Err(Error::new(
+                    name.span(),
+                    "parsing attributes go on the enum's variants and fields, not the enum",
+                ));
+            }
+
+            let mut alternatives = Vec::with_capacity(variants.len());
+            for variant in variants {
+                let ident = &variant.ident;
+                alternatives.push(context.constructor(
+                    quote!(Self::#ident),
+                    &variant.attrs,
+                    &variant.fields,
+                )?);
+            }
+
+            let Some((first, _)) = alternatives.first() else {
+                return Err(Error::new(
+                    name.span(),
+                    "`Parse` can't be derived for enums without variants",
+                ));
+            };
+
+            if alternatives.iter().all(|(_, peek)| peek.is_none()) {
+                alternatives[1..].iter().fold(first.clone(), |parser, (next, _)| {
+                    quote!(::chumsky_proc::__private::chumsky::Parser::or(#parser, #next))
+                })
+            } else {
+                let alternatives = alternatives.iter().map(|(parser, peek)| {
+                    let peek = match peek {
+                        Some((peek, _)) => quote!(::core::option::Option::Some(#peek)),
+                        None => quote!(::core::option::Option::None),
+                    };
+                    quote! {
+                        (#peek, ::chumsky_proc::__private::chumsky::Parser::boxed(#parser))
+                    }
+                });
+                quote!(::chumsky_proc::parse::peek_choice(
+                    ::std::vec![#(#alternatives),*]
+                ))
+            }
+        }
+    };
+
+    let parser = if context.recursive {
+        quote!(::chumsky_proc::__private::chumsky::recursive::recursive(|__self_parser| #parser))
+    } else {
+        parser
+    };
+
+    Ok(quote! {
+        impl ::chumsky_proc::parse::Parse for #name {
+            fn parser<
+                __E: ::chumsky_proc::__private::chumsky::error::Error<
+                        ::chumsky_proc::RustToken,
+                        Span = ::chumsky_proc::RustSpan,
+                    > + 'static,
+            >() -> impl ::chumsky_proc::__private::chumsky::Parser<
+                ::chumsky_proc::RustToken,
+                Self,
+                Error = __E,
+            > + ::core::clone::Clone {
+                #parser
+            }
+        }
+    })
+}
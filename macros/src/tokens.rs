@@ -0,0 +1,269 @@
+//! The `rust_tokens!` macro, turning quote-like token patterns into parsers
+
+use proc_macro2::{Delimiter, Ident, Literal, Spacing, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+
+use crate::error::Error;
+
+/// The placeholder kinds which map directly onto a chumsky-proc parser
+pub(crate) const KINDS: &[&str] = &[
+    "attrs", "block", "expr", "generics", "ident", "item", "lifetime", "literal", "meta", "pat",
+    "path", "punct", "tt", "ty", "vis",
+];
+
+/// A single part of a token pattern
+enum Elem {
+    /// An identifier or keyword, matched exactly
+    Keyword(Ident),
+    /// A run of joined punctuation, such as `+=` or `::`, with the span of its first character
+    Puncts(String, Span),
+    /// A literal, matched by its text
+    Literal(Literal),
+    /// A delimited group, whose contents are matched in turn
+    Group(Delimiter, Vec<Elem>),
+    /// A placeholder, such as `#name:ident`, capturing the output of a parser
+    Capture(Ident, TokenStream),
+}
+
+/// Expand a `rust_tokens!` invocation into a parser expression
+pub(crate) fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+    let mut trees = input.into_iter().collect::<Vec<_>>();
+    let output = split_output(&mut trees);
+
+    let mut names = Vec::new();
+    let elems = parse_elems(trees, &mut names)?;
+    let (parser, pattern) = sequence(&elems);
+
+    let value = match (output, names.as_slice()) {
+        (Some(path), names) => quote!(#path { #(#names),* }),
+        (None, []) => quote!(()),
+        (None, [name]) => quote!(#name),
+        (None, names) => quote!((#(#names),*)),
+    };
+
+    Ok(quote! {
+        ::chumsky_proc::__private::chumsky::Parser::map(#parser, |#pattern| #value)
+    })
+}
+
+/// Remove a trailing `=> Path` from the pattern, returning the path of the struct to build
+fn split_output(trees: &mut Vec<TokenTree>) -> Option<TokenStream> {
+    let arrow = trees.windows(2).rposition(|pair| match pair {
+        [TokenTree::Punct(eq), TokenTree::Punct(gt)] => {
+            eq.as_char() == '=' && eq.spacing() == Spacing::Joint && gt.as_char() == '>'
+        }
+        _ => false,
+    })?;
+
+    let path = &trees[arrow + 2..];
+    let is_path = !path.is_empty()
+        && path.iter().all(|tree| match tree {
+            TokenTree::Ident(_) => true,
+            TokenTree::Punct(punct) => punct.as_char() == ':',
+            _ => false,
+        });
+    if !is_path {
+        return None;
+    }
+
+    let path = path.iter().cloned().collect();
+    trees.truncate(arrow);
+    Some(path)
+}
+
+/// Get the parser for a built-in placeholder kind, such as `ident`
+pub(crate) fn builtin(kind: &Ident) -> Option<TokenStream> {
+    let span = kind.span();
+    let parser = match &*kind.to_string() {
+        "attrs" => quote!(::chumsky_proc::syntax::attr::outer_attrs()),
+        "block" => quote!(::chumsky_proc::syntax::stmt::block()),
+        "expr" => quote!(::chumsky_proc::syntax::expr::expr()),
+        "generics" => quote!(::chumsky_proc::syntax::generics::generics()),
+        "ident" => quote!(::chumsky_proc::primitive::ident()),
+        "item" => quote!(::chumsky_proc::syntax::item::item()),
+        "lifetime" => quote!(::chumsky_proc::syntax::lifetime()),
+        "literal" => quote! {
+            ::chumsky_proc::__private::chumsky::prelude::filter_map(
+                ::chumsky_proc::RustToken::filter_literal,
+            )
+        },
+        "meta" => quote!(::chumsky_proc::syntax::attr::meta()),
+        "pat" => quote!(::chumsky_proc::syntax::pat::pat()),
+        "path" => quote!(::chumsky_proc::syntax::path::path()),
+        "punct" => quote! {
+            ::chumsky_proc::__private::chumsky::prelude::filter_map(
+                ::chumsky_proc::RustToken::filter_punct,
+            )
+        },
+        "tt" => quote!(::chumsky_proc::primitive::token_tree()),
+        "ty" => quote!(::chumsky_proc::syntax::ty::ty()),
+        "vis" => quote!(::chumsky_proc::syntax::vis::visibility()),
+        _ => return None,
+    };
+    Some(quote_spanned!(span=> #parser))
+}
+
+/// Get the parser for the kind of a placeholder, either built in or a parenthesized expression
+fn placeholder(kind: TokenTree) -> Result<TokenStream, Error> {
+    match kind {
+        TokenTree::Ident(kind) => builtin(&kind).ok_or_else(|| {
+            Error::new(
+                kind.span(),
+                format!(
+                    "unknown placeholder kind `{}`, expected one of {}, or a parser in \
+                     parentheses",
+                    kind,
+                    KINDS.join(", "),
+                ),
+            )
+        }),
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {
+            Ok(group.stream())
+        }
+        kind => Err(Error::new(
+            kind.span(),
+            "expected a placeholder kind, such as `ident`, or a parser in parentheses",
+        )),
+    }
+}
+
+/// Parse a token pattern into its parts, adding the name of each capture to `names`
+fn parse_elems(trees: Vec<TokenTree>, names: &mut Vec<Ident>) -> Result<Vec<Elem>, Error> {
+    let mut elems = Vec::new();
+    let mut trees = trees.into_iter().peekable();
+
+    while let Some(tree) = trees.next() {
+        match tree {
+            TokenTree::Punct(pound) if pound.as_char() == '#' => {
+                let Some(TokenTree::Ident(name)) = trees.peek().cloned() else {
+                    elems.push(Elem::Puncts("#".to_string(), pound.span()));
+                    continue;
+                };
+                trees.next();
+
+                match (trees.next(), trees.next()) {
+                    (Some(TokenTree::Punct(colon)), Some(kind))
+                        if colon.as_char() == ':' && colon.spacing() == Spacing::Alone =>
+                    {
+                        let parser = placeholder(kind)?;
+                        if name != "_" {
+                            if names.contains(&name) {
+                                return Err(Error::new(
+                                    name.span(),
+                                    format!("duplicate placeholder `#{}`", name),
+                                ));
+                            }
+                            names.push(name.clone());
+                        }
+                        elems.push(Elem::Capture(name, parser));
+                    }
+                    _ => {
+                        return Err(Error::new(
+                            name.span(),
+                            format!(
+                                "expected a kind after `#{}`, such as `#{}:ident`",
+                                name, name
+                            ),
+                        ))
+                    }
+                }
+            }
+            TokenTree::Punct(first) => {
+                let mut text = first.as_char().to_string();
+                let mut spacing = first.spacing();
+                while spacing == Spacing::Joint {
+                    match trees.peek() {
+                        Some(TokenTree::Punct(next)) => {
+                            text.push(next.as_char());
+                            spacing = next.spacing();
+                            trees.next();
+                        }
+                        _ => break,
+                    }
+                }
+                elems.push(Elem::Puncts(text, first.span()));
+            }
+            TokenTree::Ident(ident) => elems.push(Elem::Keyword(ident)),
+            TokenTree::Literal(lit) => elems.push(Elem::Literal(lit)),
+            TokenTree::Group(group) => elems.push(Elem::Group(
+                group.delimiter(),
+                parse_elems(group.stream().into_iter().collect(), names)?,
+            )),
+        }
+    }
+
+    Ok(elems)
+}
+
+/// Build the parser for a single part of a pattern, alongside the pattern destructuring its
+/// output
+fn elem(elem: &Elem) -> (TokenStream, TokenStream) {
+    match elem {
+        Elem::Keyword(ident) => {
+            let text = ident.to_string();
+            (
+                quote_spanned!(ident.span()=> ::chumsky_proc::primitive::keyword(#text)),
+                quote!(_),
+            )
+        }
+        Elem::Puncts(text, span) => {
+            let parser = if text.chars().count() == 1 {
+                let c = text.chars().next();
+                quote_spanned!(*span=> ::chumsky_proc::primitive::punct(#c))
+            } else {
+                quote_spanned!(*span=> ::chumsky_proc::primitive::joined_punct(#text))
+            };
+            (parser, quote!(_))
+        }
+        Elem::Literal(lit) => {
+            let text = lit.to_string();
+            (
+                quote_spanned! {lit.span()=>
+                    ::chumsky_proc::__private::chumsky::prelude::filter(
+                        |tok: &::chumsky_proc::RustToken| {
+                            tok.as_literal().is_some_and(|lit| lit.to_string() == #text)
+                        },
+                    )
+                },
+                quote!(_),
+            )
+        }
+        Elem::Group(delimiter, elems) => {
+            let delimiter = match delimiter {
+                Delimiter::Parenthesis => quote!(Parenthesis),
+                Delimiter::Brace => quote!(Brace),
+                Delimiter::Bracket => quote!(Bracket),
+                Delimiter::None => quote!(None),
+            };
+            let (inner, pattern) = sequence(elems);
+            (
+                quote! {
+                    ::chumsky_proc::primitive::group(
+                        ::chumsky_proc::__private::proc_macro2::Delimiter::#delimiter,
+                        #inner,
+                    )
+                },
+                pattern,
+            )
+        }
+        Elem::Capture(name, parser) => (parser.clone(), quote!(#name)),
+    }
+}
+
+/// Build the parser for a sequence of parts, alongside the pattern destructuring its output
+fn sequence(elems: &[Elem]) -> (TokenStream, TokenStream) {
+    let mut elems = elems.iter().map(elem);
+    let Some(first) = elems.next() else {
+        return (
+            quote!(::chumsky_proc::__private::chumsky::prelude::empty()),
+            quote!(()),
+        );
+    };
+
+    elems.fold(first, |(parser, pattern), (next, next_pattern)| {
+        (
+            quote!(::chumsky_proc::__private::chumsky::Parser::then(#parser, #next)),
+            quote!((#pattern, #next_pattern)),
+        )
+    })
+}
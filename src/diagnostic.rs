@@ -0,0 +1,42 @@
+//! Native compiler diagnostics, using the nightly-only `proc_macro::Diagnostic` API
+
+use proc_macro::{Diagnostic, Level};
+
+use crate::{RustError, Warning};
+
+/// Emit an error as a native diagnostic, returning `false` when not inside a proc-macro
+pub(crate) fn emit_error(error: &RustError) -> bool {
+    if !proc_macro::is_available() {
+        return false;
+    }
+
+    let mut diagnostic = Diagnostic::spanned(error.span().unwrap(), Level::Error, error.message());
+    for (span, message) in error.secondary() {
+        diagnostic = diagnostic.span_note(span.unwrap(), message);
+    }
+    finish(diagnostic, error.notes(), error.all_help());
+    true
+}
+
+/// Emit a warning as a native diagnostic, returning `false` when not inside a proc-macro
+pub(crate) fn emit_warning(warning: &Warning) -> bool {
+    if !proc_macro::is_available() {
+        return false;
+    }
+
+    let diagnostic =
+        Diagnostic::spanned(warning.span().unwrap(), Level::Warning, warning.message());
+    finish(diagnostic, warning.notes(), warning.help().iter().cloned());
+    true
+}
+
+/// Add notes and help to a diagnostic, then emit it
+fn finish(mut diagnostic: Diagnostic, notes: &[String], help: impl IntoIterator<Item = String>) {
+    for note in notes {
+        diagnostic = diagnostic.note(note);
+    }
+    for help in help {
+        diagnostic = diagnostic.help(help);
+    }
+    diagnostic.emit();
+}
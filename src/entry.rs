@@ -0,0 +1,354 @@
+//! Entry points for proc-macros, which run a parser and then code generation, reporting every
+//! error as a spanned `compile_error!`
+//!
+//! Panics during code generation are caught and reported as an error spanning the macro input,
+//! rather than the compiler's generic "proc macro panicked" message. The entry points take and
+//! return `proc_macro2` token streams, so convert with `.into()` at the macro boundary.
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use proc_macro2::{Span, TokenStream};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{stream_with_origin, RustError, RustSpan, RustToken, StreamOrigin};
+
+/// Options for running a proc-macro's parser and code generation. The [`function_like`],
+/// [`attribute`] and [`derive`] functions use the default options.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::entry::Entry;
+/// # use chumsky::prelude::*;
+/// # use quote::quote;
+/// let parser = ident()
+///     .validate(|name, span, emit| {
+///         if name.to_string().starts_with(char::is_uppercase) {
+///             emit(RustError::custom(span, "names must be lowercase"));
+///         }
+///         name
+///     })
+///     .separated_by(punct(','))
+///     .then_ignore(end());
+///
+/// // The names are still generated, alongside the error for `B`
+/// let output = Entry::new().best_effort(true).function_like(
+///     quote!(a, B, c),
+///     parser,
+///     |names| quote!(#(let #names = ();)*),
+/// );
+/// assert!(output.to_string().starts_with("let a = () ;"));
+/// assert!(output.to_string().contains("compile_error"));
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Entry {
+    /// Whether to generate output from recovered parses, alongside their errors
+    best_effort: bool,
+}
+
+impl Entry {
+    /// Create the default options, which only generate output when parsing succeeded
+    #[must_use]
+    pub fn new() -> Entry {
+        Entry::default()
+    }
+
+    /// Also generate output when the parser recovered from errors, alongside the errors. This
+    /// keeps code after the macro, and completions inside it, working while it's being written.
+    #[must_use]
+    pub fn best_effort(mut self, best_effort: bool) -> Entry {
+        self.best_effort = best_effort;
+        self
+    }
+
+    /// Run a function-like macro, parsing its input and passing the output to `codegen`
+    pub fn function_like<O, P, F>(self, input: TokenStream, parser: P, codegen: F) -> TokenStream
+    where
+        P: Parser<RustToken, O, Error = RustError>,
+        F: FnOnce(O) -> TokenStream,
+    {
+        let span = span_of(&input);
+        let (output, mut errors) = self.parse(input, parser);
+        let tokens = output.and_then(|output| generate(span, || codegen(output), &mut errors));
+        render(tokens, &errors)
+    }
+
+    /// Run an attribute macro, parsing its arguments and the item it's attached to and passing
+    /// both outputs to `codegen`. If no output can be generated, the item is emitted unchanged
+    /// alongside the errors, so code using it doesn't report errors of its own.
+    pub fn attribute<A, I, PA, PI, F>(
+        self,
+        args: TokenStream,
+        item: TokenStream,
+        args_parser: PA,
+        item_parser: PI,
+        codegen: F,
+    ) -> TokenStream
+    where
+        PA: Parser<RustToken, A, Error = RustError>,
+        PI: Parser<RustToken, I, Error = RustError>,
+        F: FnOnce(A, I) -> TokenStream,
+    {
+        let span = span_of(&item).with_origin(StreamOrigin::Item);
+        let AttributeParse {
+            args,
+            item: parsed,
+            mut errors,
+        } = parse_attribute(args, item.clone(), args_parser, item_parser);
+
+        let tokens = args
+            .zip(parsed)
+            .filter(|_| errors.is_empty() || self.best_effort)
+            .and_then(|(args, parsed)| generate(span, || codegen(args, parsed), &mut errors));
+        render(tokens.or(Some(item)), &errors)
+    }
+
+    /// Run a derive macro, parsing the item it's attached to and passing the output to `codegen`
+    pub fn derive<O, P, F>(self, item: TokenStream, parser: P, codegen: F) -> TokenStream
+    where
+        P: Parser<RustToken, O, Error = RustError>,
+        F: FnOnce(O) -> TokenStream,
+    {
+        self.function_like(item, parser, codegen)
+    }
+
+    /// Run a parser, keeping its output only if it succeeded or best-effort output is on
+    fn parse<O, P>(self, input: TokenStream, parser: P) -> (Option<O>, Vec<RustError>)
+    where
+        P: Parser<RustToken, O, Error = RustError>,
+    {
+        let (output, errors) = parse_stream(input, StreamOrigin::Input, parser);
+        if errors.is_empty() || self.best_effort {
+            (output, errors)
+        } else {
+            (None, errors)
+        }
+    }
+}
+
+/// The result of parsing an attribute macro's arguments and item together. Each output is present
+/// if its parser succeeded or recovered, and the errors of both are kept, arguments first.
+#[derive(Clone, Debug)]
+pub struct AttributeParse<A, I, E> {
+    /// The output of the arguments' parser
+    pub args: Option<A>,
+    /// The output of the item's parser
+    pub item: Option<I>,
+    /// Every error from both parsers
+    pub errors: Vec<E>,
+}
+
+impl<A, I, E> AttributeParse<A, I, E> {
+    /// Get both outputs if both parsers succeeded without errors, or every error otherwise
+    ///
+    /// # Errors
+    ///
+    /// If either parser reported an error
+    pub fn into_result(self) -> Result<(A, I), Vec<E>> {
+        match (self.args, self.item) {
+            (Some(args), Some(item)) if self.errors.is_empty() => Ok((args, item)),
+            _ => Err(self.errors),
+        }
+    }
+}
+
+/// Parse an attribute macro's arguments and the item it's attached to, each with its own parser.
+/// Spans are tagged with [`StreamOrigin::Args`] or [`StreamOrigin::Item`], and an unexpected end
+/// of the arguments points at their last token, or at the attribute when there are none.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::entry::parse_attribute;
+/// # use chumsky_proc::StreamOrigin;
+/// # use chumsky::prelude::*;
+/// # use quote::quote;
+/// let parse = |args| {
+///     parse_attribute::<_, _, RustError, _, _>(
+///         args,
+///         quote!(fn handler() {}),
+///         ident().separated_by(punct(',')).then_ignore(end()),
+///         keyword("fn").ignore_then(ident()).then_ignore(token_tree().repeated()),
+///     )
+/// };
+///
+/// let (methods, name) = parse(quote!(get, post)).into_result().unwrap();
+/// assert_eq!(methods.len(), 2);
+/// assert_eq!(name, "handler");
+///
+/// let errs = parse(quote!(get post)).into_result().unwrap_err();
+/// assert_eq!(errs[0].span().origin(), StreamOrigin::Args);
+/// ```
+pub fn parse_attribute<A, I, E, PA, PI>(
+    args: TokenStream,
+    item: TokenStream,
+    args_parser: PA,
+    item_parser: PI,
+) -> AttributeParse<A, I, E>
+where
+    E: Error<RustToken, Span = RustSpan>,
+    PA: Parser<RustToken, A, Error = E>,
+    PI: Parser<RustToken, I, Error = E>,
+{
+    let (args, mut errors) = parse_stream(args, StreamOrigin::Args, args_parser);
+    let (item, item_errors) = parse_stream(item, StreamOrigin::Item, item_parser);
+    errors.extend(item_errors);
+    AttributeParse { args, item, errors }
+}
+
+/// Run a function-like macro, parsing its input and passing the output to `codegen`. Parse errors
+/// and panics in `codegen` are reported as `compile_error!`s.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::entry::function_like;
+/// # use chumsky::prelude::*;
+/// # use quote::quote;
+/// let output = function_like(quote!(foo), ident().then_ignore(end()), |name| {
+///     quote!(fn #name() {})
+/// });
+/// assert_eq!(output.to_string(), "fn foo () { }");
+///
+/// // A panic while generating code becomes an error spanning the input
+/// let output = function_like(quote!(foo), ident().then_ignore(end()), |_| {
+///     panic!("not yet supported")
+/// });
+/// assert!(output.to_string().contains("not yet supported"));
+/// ```
+pub fn function_like<O, P, F>(input: TokenStream, parser: P, codegen: F) -> TokenStream
+where
+    P: Parser<RustToken, O, Error = RustError>,
+    F: FnOnce(O) -> TokenStream,
+{
+    Entry::new().function_like(input, parser, codegen)
+}
+
+/// Run an attribute macro, parsing its arguments and the item it's attached to and passing both
+/// outputs to `codegen`. On errors, the item is emitted unchanged alongside them.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::entry::attribute;
+/// # use chumsky::prelude::*;
+/// # use quote::quote;
+/// let item = quote!(fn handler() {});
+/// let run = |args| {
+///     attribute(
+///         args,
+///         item.clone(),
+///         ident().then_ignore(end()),
+///         token_tree().repeated(),
+///         |method, _| quote!(const METHOD: &str = stringify!(#method);),
+///     )
+/// };
+///
+/// assert!(run(quote!(get)).to_string().starts_with("const METHOD"));
+///
+/// let output = run(quote!(get, post)).to_string();
+/// assert!(output.starts_with("fn handler"));
+/// assert!(output.contains("compile_error"));
+/// ```
+pub fn attribute<A, I, PA, PI, F>(
+    args: TokenStream,
+    item: TokenStream,
+    args_parser: PA,
+    item_parser: PI,
+    codegen: F,
+) -> TokenStream
+where
+    PA: Parser<RustToken, A, Error = RustError>,
+    PI: Parser<RustToken, I, Error = RustError>,
+    F: FnOnce(A, I) -> TokenStream,
+{
+    Entry::new().attribute(args, item, args_parser, item_parser, codegen)
+}
+
+/// Run a derive macro, parsing the item it's attached to and passing the output to `codegen`.
+/// Parse errors and panics in `codegen` are reported as `compile_error!`s.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::entry::derive;
+/// # use chumsky::prelude::*;
+/// # use quote::quote;
+/// let parser = keyword("struct")
+///     .ignore_then(ident())
+///     .then_ignore(punct(';'))
+///     .then_ignore(end());
+/// let codegen = |name| quote!(impl Marker for #name {});
+///
+/// let output = derive(quote!(struct Unit;), parser.clone(), codegen);
+/// assert_eq!(output.to_string(), "impl Marker for Unit { }");
+///
+/// let output = derive(quote!(enum Never {}), parser, codegen);
+/// assert!(output.to_string().starts_with(":: core :: compile_error"));
+/// ```
+pub fn derive<O, P, F>(item: TokenStream, parser: P, codegen: F) -> TokenStream
+where
+    P: Parser<RustToken, O, Error = RustError>,
+    F: FnOnce(O) -> TokenStream,
+{
+    Entry::new().derive(item, parser, codegen)
+}
+
+/// Run a parser over a stream with the given origin, with recovery
+fn parse_stream<O, E, P>(input: TokenStream, origin: StreamOrigin, parser: P) -> (Option<O>, Vec<E>)
+where
+    E: Error<RustToken, Span = RustSpan>,
+    P: Parser<RustToken, O, Error = E>,
+{
+    parser.parse_recovery(stream_with_origin(input, origin))
+}
+
+/// Run code generation, turning a panic into an error at the given span
+fn generate(
+    span: RustSpan,
+    codegen: impl FnOnce() -> TokenStream,
+    errors: &mut Vec<RustError>,
+) -> Option<TokenStream> {
+    panic::catch_unwind(AssertUnwindSafe(codegen))
+        .map_err(|payload| {
+            errors.push(RustError::custom(
+                span,
+                format!("code generation panicked: {}", panic_message(&*payload)),
+            ));
+        })
+        .ok()
+}
+
+/// Get the message a panic was raised with
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
+/// Get a span covering the whole input, as far as the compiler allows
+fn span_of(input: &TokenStream) -> RustSpan {
+    let mut tokens = input.clone().into_iter();
+    match (tokens.next(), tokens.last()) {
+        (Some(first), Some(last)) => RustSpan::from(first.span()).join_lossy(last.span().into()),
+        (Some(first), None) => first.span().into(),
+        (None, _) => Span::call_site().into(),
+    }
+}
+
+/// Combine the generated output, if any, with every error
+fn render(output: Option<TokenStream>, errors: &[RustError]) -> TokenStream {
+    let mut tokens = output.unwrap_or_default();
+    tokens.extend(errors.iter().map(RustError::emit));
+    tokens
+}
@@ -0,0 +1,378 @@
+//! An error type for parsers over Rust tokens, which can be rendered as compiler errors
+
+use chumsky::error::Error;
+use proc_macro2::{Delimiter, TokenStream};
+use quote::quote_spanned;
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::utils::closest_match;
+use crate::{RustSpan, RustToken};
+
+/// The reason a [`RustError`] occurred
+#[derive(Clone, Debug)]
+pub enum RustErrorReason {
+    /// An unexpected token, or the end of input, was found
+    Unexpected,
+    /// A delimiter was opened but never closed
+    Unclosed {
+        /// The span of the unclosed delimiter
+        span: RustSpan,
+        /// The unclosed delimiter
+        delimiter: RustToken,
+    },
+    /// An error with a custom message, such as from a validation step
+    Custom(String),
+}
+
+/// A parse error over Rust tokens. Unlike chumsky's built-in errors, it can carry a custom
+/// message, labels at other spans, notes and help, and renders as a `compile_error!` at the
+/// offending span. With the `nightly` feature, it's reported as a native compiler diagnostic
+/// instead.
+#[derive(Clone, Debug)]
+pub struct RustError {
+    /// The span the error occurred at
+    span: RustSpan,
+    /// Why the error occurred
+    reason: RustErrorReason,
+    /// The tokens which would have been accepted, with `None` meaning the end of input
+    expected: HashSet<Option<RustToken>>,
+    /// The token which was found, or `None` for the end of input
+    found: Option<RustToken>,
+    /// The label of the parser which failed, if any
+    label: Option<&'static str>,
+    /// Messages pointing at other spans, such as a previous definition
+    secondary: Vec<(RustSpan, String)>,
+    /// Extra notes to show alongside the message
+    notes: Vec<String>,
+    /// Suggestions to show alongside the message
+    help: Vec<String>,
+}
+
+impl RustError {
+    /// Create an error with a custom message at the given span
+    #[must_use]
+    pub fn custom(span: RustSpan, message: impl ToString) -> RustError {
+        RustError {
+            span,
+            reason: RustErrorReason::Custom(message.to_string()),
+            expected: HashSet::new(),
+            found: None,
+            label: None,
+            secondary: Vec::new(),
+            notes: Vec::new(),
+            help: Vec::new(),
+        }
+    }
+
+    /// Add a message pointing at another span, such as ``previous definition of `x` here``
+    #[must_use]
+    pub fn with_secondary(mut self, span: RustSpan, message: impl ToString) -> RustError {
+        self.secondary.push((span, message.to_string()));
+        self
+    }
+
+    /// Add a note to this error, such as an explanation of why the input isn't allowed
+    #[must_use]
+    pub fn with_note(mut self, note: impl ToString) -> RustError {
+        self.notes.push(note.to_string());
+        self
+    }
+
+    /// Add a help note to this error, such as ``did you mean `rename`?``
+    #[must_use]
+    pub fn with_help(mut self, help: impl ToString) -> RustError {
+        self.help.push(help.to_string());
+        self
+    }
+
+    /// Get the span this error occurred at
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        self.span
+    }
+
+    /// Get the reason this error occurred
+    #[must_use]
+    pub fn reason(&self) -> &RustErrorReason {
+        &self.reason
+    }
+
+    /// Get the tokens which would have been accepted, with `None` meaning the end of input
+    pub fn expected(&self) -> impl Iterator<Item = &Option<RustToken>> {
+        self.expected.iter()
+    }
+
+    /// Get the token which was found, or `None` for the end of input
+    #[must_use]
+    pub fn found(&self) -> Option<&RustToken> {
+        self.found.as_ref()
+    }
+
+    /// Get the label of the parser which failed, if any
+    #[must_use]
+    pub fn label(&self) -> Option<&'static str> {
+        self.label
+    }
+
+    /// Get the messages attached to other spans, including the opening delimiter of an
+    /// [unclosed][RustErrorReason::Unclosed] delimiter
+    pub fn secondary(&self) -> impl Iterator<Item = (RustSpan, &str)> {
+        let unclosed = match &self.reason {
+            RustErrorReason::Unclosed { span, .. } => Some((*span, "unclosed delimiter")),
+            _ => None,
+        };
+        unclosed.into_iter().chain(
+            self.secondary
+                .iter()
+                .map(|(span, message)| (*span, message.as_str())),
+        )
+    }
+
+    /// Get the notes attached to this error
+    #[must_use]
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    /// Get the help notes attached to this error
+    #[must_use]
+    pub fn help(&self) -> &[String] {
+        &self.help
+    }
+
+    /// Get a suggestion for a misspelled identifier, when one was found where similarly spelled
+    /// identifiers, such as keywords, were expected. It's rendered as a help note, like
+    /// ``did you mean `mol`?``. Ties are broken alphabetically.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky_proc::prelude::*;
+    /// # use chumsky::prelude::*;
+    /// # use quote::quote;
+    /// let parser = keyword::<RustError>("mol").or(keyword("kg")).labelled("unit");
+    ///
+    /// let errs = parser.parse(stream_from_tokens(quote!(mols))).unwrap_err();
+    /// assert_eq!(errs[0].suggestion().as_deref(), Some("mol"));
+    /// assert_eq!(
+    ///     errs[0].to_string(),
+    ///     "unexpected `mols` while parsing unit, expected `kg` or `mol`\nhelp: did you mean `mol`?",
+    /// );
+    ///
+    /// // Nothing is suggested when no keyword is close enough
+    /// let errs = parser.parse(stream_from_tokens(quote!(meters))).unwrap_err();
+    /// assert_eq!(errs[0].suggestion(), None);
+    ///
+    /// // `kg` and `km` are equally close to `kx`
+    /// let parser = keyword::<RustError>("km").or(keyword("kg"));
+    /// let errs = parser.parse(stream_from_tokens(quote!(kx))).unwrap_err();
+    /// assert_eq!(errs[0].suggestion().as_deref(), Some("kg"));
+    /// ```
+    #[must_use]
+    pub fn suggestion(&self) -> Option<String> {
+        let found = match (&self.reason, &self.found) {
+            (RustErrorReason::Unexpected, Some(RustToken::Ident(found))) => found.to_string(),
+            _ => return None,
+        };
+        // The expected set is unordered, so sort it to break ties consistently
+        let mut candidates = self
+            .expected
+            .iter()
+            .filter_map(|tok| tok.as_ref()?.as_ident().map(ToString::to_string))
+            .collect::<Vec<_>>();
+        candidates.sort_unstable();
+        closest_match(&found, candidates.iter().map(String::as_str)).map(str::to_string)
+    }
+
+    /// Get every help note to show, starting with the [suggestion][RustError::suggestion] if any
+    pub(crate) fn all_help(&self) -> impl Iterator<Item = String> + '_ {
+        self.suggestion()
+            .map(|suggestion| format!("did you mean `{}`?", suggestion))
+            .into_iter()
+            .chain(self.help.iter().cloned())
+    }
+
+    /// Get the main message of this error, without its secondary labels, notes or help
+    #[must_use]
+    pub fn message(&self) -> String {
+        match &self.reason {
+            RustErrorReason::Custom(message) => message.clone(),
+            RustErrorReason::Unclosed { delimiter, .. } => {
+                format!("unclosed delimiter {}", describe(Some(delimiter)))
+            }
+            RustErrorReason::Unexpected => {
+                unexpected_message(self.found.as_ref(), self.label, self.expected.iter())
+            }
+        }
+    }
+
+    /// Render this error as a `compile_error!` invocation at its span
+    #[must_use]
+    pub fn to_compile_error(&self) -> TokenStream {
+        let message = self.to_string();
+        quote_spanned!(*self.span => ::core::compile_error! { #message })
+    }
+
+    /// Report this error from a proc-macro. With the `nightly` feature, while a macro is
+    /// expanding, it's emitted as a native compiler diagnostic with its secondary labels, notes
+    /// and help, and no tokens are returned. Otherwise, it's rendered as a `compile_error!`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky_proc::prelude::*;
+    /// # use proc_macro2::Span;
+    /// let first = Span::call_site().into();
+    /// let error = RustError::custom(Span::call_site().into(), "duplicate field `x`")
+    ///     .with_secondary(first, "first defined here")
+    ///     .with_help("rename one of the fields");
+    ///
+    /// // Outside of a proc-macro, this is always a `compile_error!`
+    /// let tokens = error.emit().to_string();
+    /// assert!(tokens.contains("duplicate field `x`\\nnote: first defined here"));
+    /// ```
+    #[must_use]
+    pub fn emit(&self) -> TokenStream {
+        #[cfg(feature = "nightly")]
+        if crate::diagnostic::emit_error(self) {
+            return TokenStream::new();
+        }
+        self.to_compile_error()
+    }
+
+    /// Render this error as a `compile_error!` invocation at its span, ready to be returned from
+    /// a proc-macro. This is a convenience wrapper converting the output of
+    /// [`to_compile_error`][RustError::to_compile_error], which should be used in tests instead,
+    /// as the `proc_macro` API only works while a macro is expanding.
+    #[cfg(feature = "proc-macro")]
+    #[must_use]
+    pub fn to_proc_macro_error(&self) -> proc_macro::TokenStream {
+        self.to_compile_error().into()
+    }
+}
+
+impl fmt::Display for RustError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())?;
+        for (_, message) in &self.secondary {
+            write!(f, "\nnote: {}", message)?;
+        }
+        for note in &self.notes {
+            write!(f, "\nnote: {}", note)?;
+        }
+        for help in self.all_help() {
+            write!(f, "\nhelp: {}", help)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RustError {}
+
+impl Error<RustToken> for RustError {
+    type Span = RustSpan;
+    type Label = &'static str;
+
+    fn expected_input_found<Iter: IntoIterator<Item = Option<RustToken>>>(
+        span: Self::Span,
+        expected: Iter,
+        found: Option<RustToken>,
+    ) -> Self {
+        RustError {
+            span,
+            reason: RustErrorReason::Unexpected,
+            expected: expected.into_iter().collect(),
+            found,
+            label: None,
+            secondary: Vec::new(),
+            notes: Vec::new(),
+            help: Vec::new(),
+        }
+    }
+
+    fn unclosed_delimiter(
+        unclosed_span: Self::Span,
+        unclosed: RustToken,
+        span: Self::Span,
+        expected: RustToken,
+        found: Option<RustToken>,
+    ) -> Self {
+        RustError {
+            span,
+            reason: RustErrorReason::Unclosed {
+                span: unclosed_span,
+                delimiter: unclosed,
+            },
+            expected: std::iter::once(Some(expected)).collect(),
+            found,
+            label: None,
+            secondary: Vec::new(),
+            notes: Vec::new(),
+            help: Vec::new(),
+        }
+    }
+
+    fn with_label(mut self, label: Self::Label) -> Self {
+        self.label.get_or_insert(label);
+        self
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        // Custom and unclosed errors are more specific than unexpected tokens, so keep them
+        if let (RustErrorReason::Unexpected, RustErrorReason::Unexpected) =
+            (&self.reason, &other.reason)
+        {
+            self.expected.extend(other.expected);
+        } else if let RustErrorReason::Unexpected = self.reason {
+            return other;
+        }
+        self
+    }
+}
+
+/// Describe an unexpected token, what was being parsed and what would have been accepted
+pub(crate) fn unexpected_message<'a>(
+    found: Option<&RustToken>,
+    label: Option<&str>,
+    expected: impl Iterator<Item = &'a Option<RustToken>>,
+) -> String {
+    let mut message = format!("unexpected {}", describe(found));
+    if let Some(label) = label {
+        message += &format!(" while parsing {}", label);
+    }
+    let mut expected = expected
+        .map(|tok| describe(tok.as_ref()))
+        .collect::<Vec<_>>();
+    expected.sort();
+    expected.dedup();
+    match &*expected {
+        [] => (),
+        [one] => message += &format!(", expected {}", one),
+        [rest @ .., last] => message += &format!(", expected {} or {}", rest.join(", "), last),
+    }
+    message
+}
+
+/// Describe a token, or the end of input, for use in an error message
+pub(crate) fn describe(tok: Option<&RustToken>) -> String {
+    /// Get the characters for a delimiter, as (open, close)
+    fn delim_chars(delim: Delimiter) -> (&'static str, &'static str) {
+        match delim {
+            Delimiter::Parenthesis => ("(", ")"),
+            Delimiter::Brace => ("{", "}"),
+            Delimiter::Bracket => ("[", "]"),
+            Delimiter::None => ("invisible group", "end of invisible group"),
+        }
+    }
+
+    match tok {
+        None => String::from("end of input"),
+        Some(RustToken::Literal(lit)) => format!("literal `{}`", lit),
+        Some(RustToken::Ident(ident)) => format!("`{}`", ident),
+        Some(RustToken::Punct(punct)) => format!("`{}`", punct.as_char()),
+        Some(RustToken::StartDelim(delim)) => format!("`{}`", delim_chars(*delim).0),
+        Some(RustToken::EndDelim(delim)) => format!("`{}`", delim_chars(*delim).1),
+        Some(RustToken::DocComment(_)) => String::from("doc comment"),
+    }
+}
@@ -4,13 +4,13 @@
 //! `stream_from_tokens` with a `TokenStream` to generate a stream that can be passed
 //! to your parsers. Easy as pie!
 
+#![cfg_attr(feature = "nightly", feature(proc_macro_diagnostic))]
 #![warn(
     missing_docs,
     elided_lifetimes_in_paths,
     explicit_outlives_requirements,
     missing_abi,
     noop_method_call,
-    pointer_structural_match,
     semicolon_in_expressions_from_macros,
     unused_import_braces,
     unused_lifetimes,
@@ -27,19 +27,52 @@
     clippy::redundant_closure_for_method_calls
 )]
 
+#[cfg(feature = "proc-macro")]
+extern crate proc_macro;
+
+#[cfg(feature = "nightly")]
+mod diagnostic;
+pub mod entry;
+mod error;
+pub mod parse;
 pub mod primitive;
+pub mod recovery;
 mod regular;
+pub mod rules;
 mod span;
+#[cfg(feature = "syn")]
+pub mod syn_interop;
+pub mod syntax;
 mod token;
+pub mod tokens;
 pub(crate) mod utils;
+mod warning;
 // TODO: zero-copy, once it's released
 
+#[cfg(feature = "macros")]
+pub use chumsky_proc_macros::{grammar, rust_tokens, Keyword, Parse};
+pub use error::{RustError, RustErrorReason};
 pub use regular::*;
-pub use span::RustSpan;
+pub use span::{RustSpan, StreamOrigin};
 pub use token::RustToken;
+pub use warning::{Warning, Warnings};
 
 /// Common imports, meant to be used as `use chumsky_proc::prelude::*;`
 pub mod prelude {
+    pub use crate::parse::Parse;
     pub use crate::primitive::*;
-    pub use crate::{stream_from_tokens, RustSpan, RustToken};
+    #[cfg(feature = "proc-macro")]
+    pub use crate::stream_from_proc_macro;
+    #[cfg(feature = "syn")]
+    pub use crate::syn_interop::{parse_with_chumsky, syn_parse, ChumskyParse};
+    #[cfg(feature = "macros")]
+    pub use crate::{grammar, rust_tokens, Keyword, Parse};
+    pub use crate::{stream_from_tokens, RustError, RustSpan, RustToken};
+}
+
+/// Re-exports used by the code our macros generate. Not public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use chumsky;
+    pub use proc_macro2;
 }
@@ -0,0 +1,245 @@
+//! Types with a canonical parser, usually derived with `#[derive(Parse)]`
+//!
+//! Most of the syntax tree types in [`syntax`][crate::syntax] and every typed token in
+//! [`tokens`][crate::tokens] implement [`Parse`], so they can be used directly as the fields of
+//! a derived AST.
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use chumsky::BoxedParser;
+use proc_macro2::{Ident, Literal};
+use std::cell::OnceCell;
+use std::rc::Rc;
+
+use crate::primitive::{ident, keyword_set, Keyword};
+use crate::syntax::attr::{meta, outer_attr};
+use crate::syntax::expr::expr;
+use crate::syntax::generics::{generics, Generics};
+use crate::syntax::item::item;
+use crate::syntax::mac::macro_call;
+use crate::syntax::pat::pat;
+use crate::syntax::path::path;
+use crate::syntax::stmt::block;
+use crate::syntax::ty::ty;
+use crate::syntax::vis::visibility;
+use crate::syntax::{
+    lifetime, Attribute, Block, Expr, Item, Lifetime, MacroCall, Meta, Pat, Path, Type, Visibility,
+};
+use crate::{RustSpan, RustToken};
+
+/// A type with a canonical parser. Usually derived for the structs and enums of a DSL's syntax
+/// tree with `#[derive(Parse)]`, which parses each field in order using its type's parser.
+///
+/// # Deriving
+///
+/// Fields are parsed in order, each with the parser of its type. Fields of type `Box<T>`,
+/// `Option<T>` and `Vec<T>` use the parser of `T`, boxed, made optional or repeated. A field of
+/// type `Self` (or the type's own name), possibly wrapped like that, parses the type recursively.
+/// Other types are parsed with [`lazy`], so types may also contain each other. Other parsers can be
+/// used with `#[parse(with = parser())]`.
+///
+/// Fields, structs and enum variants accept these attributes:
+///
+/// - `#[keyword("let")]` expects a keyword first
+/// - `#[punct(':')]` or `#[punct("::")]` expects punctuation first
+///
+/// And fields also accept:
+///
+/// - `#[delimited(paren)]`, `#[delimited(brace)]` or `#[delimited(bracket)]` parses the field
+///   inside a delimited group
+/// - `#[separated(',')]` separates the elements of a `Vec` field, allowing a trailing separator
+/// - `#[peek]` commits to the enum variant once everything up to and including this field has
+///   matched, so later variants aren't tried. On a variant, it commits after its first field.
+///
+/// For an `Option` field, its keywords, punctuation and delimiters are optional along with it.
+/// Enum variants are tried in order, and the first to match is used.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::{Expr, Type};
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Simple;
+/// # use chumsky_proc::Token;
+/// # use proc_macro2::Ident;
+/// # use quote::quote;
+/// #[derive(Parse)]
+/// struct Call {
+///     name: Ident,
+///     #[delimited(paren)]
+///     #[separated(',')]
+///     args: Vec<Expr>,
+/// }
+///
+/// #[derive(Parse)]
+/// enum Stmt {
+///     #[keyword("let")]
+///     #[peek]
+///     Let {
+///         name: Ident,
+///         #[punct(':')]
+///         ty: Option<Type>,
+///         #[punct('=')]
+///         value: Expr,
+///         semi: Token![;],
+///     },
+///     Block(#[delimited(brace)] Vec<Stmt>),
+///     Call(Call),
+/// }
+///
+/// let parser = Stmt::parser::<Simple<_, RustSpan>>().repeated().then_ignore(end());
+/// let stmts = parser
+///     .parse(stream_from_tokens(quote!(let x: u8 = 1; { print(x, 2) })))
+///     .unwrap();
+/// assert!(matches!(&stmts[0], Stmt::Let { ty: Some(_), .. }));
+/// assert!(matches!(&stmts[1], Stmt::Block(inner) if inner.len() == 1));
+///
+/// // Once `let x` has matched, the statement must be a `let`
+/// assert!(parser.parse(stream_from_tokens(quote!(let x = ;))).is_err());
+/// ```
+pub trait Parse: Sized {
+    /// The parser for this type
+    #[must_use]
+    fn parser<E: Error<RustToken, Span = RustSpan> + 'static>(
+    ) -> impl Parser<RustToken, Self, Error = E> + Clone;
+}
+
+/// Accepts any keyword in the set, with [`keyword_set`]
+impl<K: Keyword> Parse for K {
+    fn parser<E: Error<RustToken, Span = RustSpan> + 'static>(
+    ) -> impl Parser<RustToken, Self, Error = E> + Clone {
+        keyword_set()
+    }
+}
+
+/// Implement [`Parse`] for types with an existing parser function
+macro_rules! impl_parse {
+    ($($ty:ty => $parser:expr,)*) => {$(
+        impl Parse for $ty {
+            fn parser<E: Error<RustToken, Span = RustSpan> + 'static>(
+            ) -> impl Parser<RustToken, Self, Error = E> + Clone {
+                $parser
+            }
+        }
+    )*};
+}
+
+impl_parse! {
+    Ident => ident(),
+    Literal => filter_map(RustToken::filter_literal),
+    Attribute => outer_attr(),
+    Block => block(),
+    Expr => expr(),
+    Generics => generics(),
+    Item => item(),
+    Lifetime => lifetime(),
+    MacroCall => macro_call(),
+    Meta => meta(),
+    Pat => pat(),
+    Path => path(),
+    Type => ty(),
+    Visibility => visibility(),
+}
+
+/// Chooses between alternatives in order, like [`choice`], except that an alternative with a
+/// lookahead is committed to once its lookahead matches. Its errors are then reported without
+/// trying any later alternatives. Lookaheads don't consume any input.
+///
+/// # Panics
+///
+/// If there are no alternatives
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::parse::peek_choice;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Simple;
+/// # use quote::quote;
+/// let parser = peek_choice::<Simple<_, RustSpan>, _>(vec![
+///     (
+///         Some(keyword("let").boxed()),
+///         keyword("let").ignore_then(ident()).map(|_| 1).boxed(),
+///     ),
+///     (None, token_tree().repeated().map(|_| 2).boxed()),
+/// ]);
+///
+/// assert_eq!(parser.parse(stream_from_tokens(quote!(let x))).unwrap(), 1);
+/// assert_eq!(parser.parse(stream_from_tokens(quote!(foo 1))).unwrap(), 2);
+///
+/// // After `let`, the first alternative is committed to, so the second isn't tried
+/// parser.parse(stream_from_tokens(quote!(let 1))).unwrap_err();
+/// ```
+#[allow(clippy::type_complexity)]
+#[must_use]
+pub fn peek_choice<E: Error<RustToken, Span = RustSpan> + 'static, O: 'static>(
+    alternatives: Vec<(
+        Option<BoxedParser<'static, RustToken, (), E>>,
+        BoxedParser<'static, RustToken, O, E>,
+    )>,
+) -> BoxedParser<'static, RustToken, O, E> {
+    let mut alternatives = alternatives.into_iter().rev();
+    let (_, mut rest) = alternatives
+        .next()
+        .expect("peek_choice needs at least one alternative");
+
+    for (lookahead, parser) in alternatives {
+        rest = match lookahead {
+            Some(lookahead) => lookahead
+                .rewind()
+                .or_not()
+                .then_with(move |matched| {
+                    if matched.is_some() {
+                        parser.clone()
+                    } else {
+                        rest.clone()
+                    }
+                })
+                .boxed(),
+            None => parser.or(rest).boxed(),
+        };
+    }
+
+    rest
+}
+
+/// Accepts a `T`, building its parser the first time it's used rather than up front. Derived
+/// parsers use this for the types of their fields, so types which contain each other, such as an
+/// `Expr` containing a `Paren` containing an `Expr`, don't build each other's parsers forever.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Simple;
+/// # use proc_macro2::Ident;
+/// # use quote::quote;
+/// #[derive(Parse)]
+/// enum Expr {
+///     Paren(Box<Paren>),
+///     Name(Ident),
+/// }
+///
+/// #[derive(Parse)]
+/// struct Paren {
+///     #[delimited(paren)]
+///     inner: Box<Expr>,
+/// }
+///
+/// let parser = Expr::parser::<Simple<_, RustSpan>>().then_ignore(end());
+/// let expr = parser.parse(stream_from_tokens(quote!(((x))))).unwrap();
+/// assert!(matches!(expr, Expr::Paren(paren) if matches!(*paren.inner, Expr::Paren(_))));
+///
+/// assert!(parser.parse(stream_from_tokens(quote!(((x)) y))).is_err());
+/// ```
+#[must_use]
+pub fn lazy<T: Parse + 'static, E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> BoxedParser<'static, RustToken, T, E> {
+    let parser = Rc::new(OnceCell::new());
+    empty()
+        .then_with(move |()| parser.get_or_init(|| T::parser().boxed()).clone())
+        .boxed()
+}
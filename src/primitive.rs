@@ -2,9 +2,9 @@
 
 use chumsky::error::Error;
 use chumsky::prelude::*;
-use proc_macro2::{Ident, Punct, Spacing, Span};
+use proc_macro2::{Delimiter, Ident, Punct, Spacing, Span};
 
-use crate::utils::punct_eq;
+use crate::utils::{is_keyword, punct_eq};
 use crate::{RustSpan, RustToken};
 
 /// Accepts only an exact identifier, output `()` on success
@@ -164,3 +164,185 @@ pub fn joined_punct<E: Error<RustToken, Span = RustSpan>>(
                 .map_err(|tok| E::expected_input_found(span, [], Some(tok)))
         }))
 }
+
+/// A fixed set of keywords, each mapping to a value. Usually derived for an enum with
+/// `#[derive(Keyword)]`, where each unit variant matches its snake-cased name, or the name given by
+/// `#[keyword = "..."]`. Every keyword set implements [`Parse`][crate::parse::Parse], using
+/// [`keyword_set`] as its parser.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Simple;
+/// # use proc_macro2::Span;
+/// # use quote::quote;
+/// #[derive(Keyword, Clone, Copy, Debug, PartialEq)]
+/// enum Unit {
+///     Kg,
+///     #[keyword = "A"]
+///     Ampere,
+///     MetersPerSecond,
+/// }
+///
+/// let parser = Unit::parser::<Simple<_, RustSpan>>();
+/// let unit = parser.parse(stream_from_tokens(quote!(meters_per_second))).unwrap();
+/// assert_eq!(unit, Unit::MetersPerSecond);
+/// assert_eq!(Unit::Ampere.to_ident(Span::call_site().into()), "A");
+///
+/// // Failing reports every keyword in the set as expected
+/// let errs = parser.parse(stream_from_tokens(quote!(kilogram))).unwrap_err();
+/// assert_eq!(errs[0].expected().count(), 3);
+/// ```
+pub trait Keyword: Sized {
+    /// Every keyword in the set
+    const KEYWORDS: &'static [&'static str];
+
+    /// Get the value of a keyword, or `None` if it isn't in the set
+    fn from_keyword(keyword: &str) -> Option<Self>;
+
+    /// Get the keyword for this value
+    fn as_keyword(&self) -> &'static str;
+
+    /// Create an identifier for this value's keyword with the given span, for generating code
+    #[must_use]
+    fn to_ident(&self, span: RustSpan) -> Ident {
+        Ident::new(self.as_keyword(), *span)
+    }
+}
+
+/// Accepts any keyword in the set with a single lookup, outputting its value. On failure, every
+/// keyword in the set is reported as expected.
+#[must_use]
+pub fn keyword_set<K: Keyword, E: Error<RustToken, Span = RustSpan>>(
+) -> impl Parser<RustToken, K, Error = E> + Clone {
+    filter_map(|span, tok: RustToken| {
+        match tok
+            .as_ident()
+            .and_then(|ident| K::from_keyword(&ident.to_string()))
+        {
+            Some(value) => Ok(value),
+            None => Err(E::expected_input_found(
+                span,
+                K::KEYWORDS
+                    .iter()
+                    .map(|keyword| Some(RustToken::Ident(Ident::new(keyword, Span::mixed_site())))),
+                Some(tok),
+            )),
+        }
+    })
+}
+
+/// Accepts any identifier which isn't a Rust keyword, outputting the identifier
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = ident::<Cheap<_, RustSpan>>();
+///
+/// assert_eq!(parser.parse(stream_from_tokens(quote!(foo))).unwrap(), "foo");
+///
+/// parser.parse(stream_from_tokens(quote!(struct))).unwrap_err();
+/// ```
+#[must_use]
+pub fn ident<E: Error<RustToken, Span = RustSpan>>(
+) -> impl Parser<RustToken, Ident, Error = E> + Clone {
+    filter_map(|span, tok: RustToken| match tok {
+        RustToken::Ident(ident) if !is_keyword(&ident.to_string()) => Ok(ident),
+        tok => Err(E::expected_input_found(span, [], Some(tok))),
+    })
+}
+
+/// Accepts a single token tree - either one token, or a whole delimited group including its
+/// delimiters. Outputs the flat tokens that were consumed, alongside their spans.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = token_tree::<Cheap<_, RustSpan>>().repeated().exactly(2);
+///
+/// let trees = parser.parse(stream_from_tokens(quote!(foo (bar, [baz])))).unwrap();
+/// assert_eq!(trees[0].len(), 1);
+/// assert_eq!(trees[1].len(), 7);
+///
+/// parser.parse(stream_from_tokens(quote!(foo))).unwrap_err();
+/// ```
+#[must_use]
+pub fn token_tree<'a, E: 'a + Error<RustToken, Span = RustSpan>>(
+) -> impl Parser<RustToken, Vec<(RustToken, RustSpan)>, Error = E> + Clone + 'a {
+    recursive(|tree| {
+        let single = filter_map(|span, tok: RustToken| {
+            if tok.is_delim() {
+                Err(E::expected_input_found(span, [], Some(tok)))
+            } else {
+                Ok(vec![(tok, span)])
+            }
+        });
+
+        let start = filter_map(|span, tok: RustToken| {
+            if tok.is_start_delim() {
+                Ok((tok, span))
+            } else {
+                Err(E::expected_input_found(span, [], Some(tok)))
+            }
+        });
+
+        // Delimiters are always balanced, so whatever end delimiter follows the inner trees
+        // must be the one matching our start.
+        let end = filter_map(|span, tok: RustToken| {
+            if tok.is_end_delim() {
+                Ok((tok, span))
+            } else {
+                Err(E::expected_input_found(span, [], Some(tok)))
+            }
+        });
+
+        single.or(start.chain(tree.repeated().flatten()).chain(end))
+    })
+}
+
+/// Accepts a delimited group, running the provided parser over its contents. The inner parser must
+/// consume everything up to the closing delimiter.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use proc_macro2::Delimiter;
+/// # use quote::quote;
+/// let parser = group::<Cheap<_, RustSpan>, _, _>(
+///     Delimiter::Parenthesis,
+///     ident().separated_by(punct(',')),
+/// );
+///
+/// let idents = parser.parse(stream_from_tokens(quote!((a, b, c)))).unwrap();
+/// assert_eq!(idents.len(), 3);
+///
+/// parser.parse(stream_from_tokens(quote!([a, b, c]))).unwrap_err();
+/// parser.parse(stream_from_tokens(quote!((a, b c)))).unwrap_err();
+/// ```
+#[must_use]
+pub fn group<E, O, P>(
+    delimiter: Delimiter,
+    inner: P,
+) -> impl Parser<RustToken, O, Error = E> + Clone
+where
+    E: Error<RustToken, Span = RustSpan>,
+    P: Parser<RustToken, O, Error = E> + Clone,
+{
+    inner.delimited_by(
+        just(RustToken::StartDelim(delimiter)),
+        just(RustToken::EndDelim(delimiter)),
+    )
+}
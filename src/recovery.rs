@@ -0,0 +1,289 @@
+//! Recovery-first parsing, for macros which should keep generating output while their input is
+//! incomplete, such as when rust-analyzer expands them on every keystroke
+//!
+//! A recovering parser reports its errors as usual, then skips the broken input and outputs an
+//! [`ErrorNode`] holding the skipped tokens in its place. Run recovering parsers with
+//! [`parse_recovery`][chumsky::Parser::parse_recovery] to get both the partial output and the
+//! errors.
+//!
+//! How much input to skip is decided by a strategy, such as [`skip_to_sync`] or [`skip_group`],
+//! passed to [`recover`]. Unlike chumsky's own strategies, these know that delimiters in a
+//! `TokenStream` are always balanced, so they skip whole token trees and never leave the group
+//! they started in.
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use chumsky::Stream;
+use proc_macro2::{Delimiter, Span, TokenStream};
+use quote::ToTokens;
+use std::cell::Cell;
+
+use crate::primitive::{punct, token_tree};
+use crate::syntax::Verbatim;
+use crate::{RustSpan, RustToken};
+
+/// The input skipped while recovering from an error, in place of what should have been parsed.
+/// Converting it to tokens gives back the skipped input, so a macro can pass it through and keep
+/// completions and highlighting working inside it.
+#[derive(Clone, Debug)]
+pub struct ErrorNode {
+    /// The skipped tokens
+    skipped: Verbatim,
+}
+
+impl ErrorNode {
+    /// Get the skipped tokens
+    #[must_use]
+    pub fn skipped(&self) -> &Verbatim {
+        &self.skipped
+    }
+
+    /// Get the span covering the skipped tokens
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        self.skipped.span()
+    }
+}
+
+impl ToTokens for ErrorNode {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.skipped.to_tokens(tokens);
+    }
+}
+
+/// Runs a parser, recovering from its failure by skipping to the next `,` or `;` at the same
+/// nesting depth, or to the end of the enclosing group. This is [`recover`] with
+/// [`skip_to_sync`], and is a good default for lists and statements.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::recovery::recovering;
+/// # use chumsky::prelude::*;
+/// # use quote::quote;
+/// let field = ident::<RustError>()
+///     .then_ignore(punct('='))
+///     .then(filter_map(RustToken::filter_literal));
+/// let parser = recovering(field).separated_by(punct(',')).then_ignore(end());
+///
+/// let (fields, errs) = parser.parse_recovery(stream_from_tokens(quote!(a = 1, b (2), c = 3)));
+/// let fields = fields.unwrap();
+/// assert!(fields[0].is_ok() && fields[2].is_ok());
+/// assert_eq!(fields[1].as_ref().unwrap_err().skipped().tokens().len(), 4);
+///
+/// assert_eq!(errs.len(), 1);
+/// assert_eq!(errs[0].to_string(), "unexpected `(`, expected `=`");
+/// ```
+#[must_use]
+pub fn recovering<O, E, P>(
+    parser: P,
+) -> impl Parser<RustToken, Result<O, ErrorNode>, Error = E> + Clone
+where
+    O: 'static,
+    E: Error<RustToken, Span = RustSpan> + 'static,
+    P: Parser<RustToken, O, Error = E> + Clone + 'static,
+{
+    recover(parser, skip_to_sync(&[punct(','), punct(';')]))
+}
+
+/// Runs a parser, and on failure reports its errors and runs the `strategy` instead, outputting
+/// the tokens it skipped as an [`ErrorNode`]. If the strategy fails too, this fails like the
+/// parser.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::recovery::{recover, skip_group};
+/// # use chumsky::prelude::*;
+/// # use proc_macro2::Delimiter;
+/// # use quote::quote;
+/// let body = group(Delimiter::Brace, ident::<RustError>().repeated());
+/// let parser = keyword("fn")
+///     .ignore_then(ident())
+///     .then(recover(body, skip_group()))
+///     .repeated()
+///     .then_ignore(end());
+///
+/// let (fns, errs) = parser.parse_recovery(stream_from_tokens(quote!(fn a { x } fn b { 1 } fn c {})));
+/// let fns = fns.unwrap();
+/// assert_eq!(fns.len(), 3);
+/// assert!(fns[1].1.is_err());
+/// assert_eq!(errs.len(), 1);
+/// ```
+#[must_use]
+pub fn recover<O, E, P, S>(
+    parser: P,
+    strategy: S,
+) -> impl Parser<RustToken, Result<O, ErrorNode>, Error = E> + Clone
+where
+    O: 'static,
+    E: Error<RustToken, Span = RustSpan> + 'static,
+    P: Parser<RustToken, O, Error = E> + Clone + 'static,
+    S: Parser<RustToken, Vec<(RustToken, RustSpan)>, Error = E> + Clone + 'static,
+{
+    // Everything up to the end of the enclosing group, and the closing delimiter itself
+    let rest = token_tree()
+        .repeated()
+        .flatten()
+        .then(
+            filter_map(|span, tok: RustToken| {
+                if tok.is_end_delim() {
+                    Ok((tok, span))
+                } else {
+                    Err(E::expected_input_found(span, [], Some(tok)))
+                }
+            })
+            .or_not(),
+        )
+        .rewind();
+
+    // The parser's errors aren't available once it has failed, so run it again over the rest of
+    // the group to get them back. It stops at the same place, so they're the same errors.
+    let fallback = rest.then_with({
+        let parser = parser.clone();
+        move |(rest, close)| {
+            let eoi = close
+                .as_ref()
+                .or_else(|| rest.last())
+                .map_or_else(|| Span::call_site().into(), |(_, span)| *span);
+            let errors = parser
+                .parse_recovery(Stream::from_iter(eoi, rest.into_iter().chain(close)))
+                .1;
+            let errors = Cell::new(errors);
+
+            strategy.clone().validate(move |tokens, span, emit| {
+                let errors = errors.take();
+                if errors.is_empty() {
+                    emit(E::expected_input_found(
+                        span,
+                        [],
+                        tokens.first().map(|(tok, _)| tok.clone()),
+                    ));
+                }
+                errors.into_iter().for_each(&mut *emit);
+                Err(ErrorNode {
+                    skipped: Verbatim::new(tokens),
+                })
+            })
+        }
+    });
+
+    parser.map(Ok).or(fallback)
+}
+
+/// A recovery strategy which skips a single delimited group, including its delimiters. It fails
+/// if the input isn't at the start of a group.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::recovery::skip_group;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = skip_group::<Cheap<_, RustSpan>>();
+///
+/// let (skipped, rest) = parser
+///     .clone()
+///     .then(token_tree().repeated().flatten())
+///     .parse(stream_from_tokens(quote!((a, [b]) c)))
+///     .unwrap();
+/// assert_eq!(skipped.len(), 7);
+/// assert_eq!(rest.len(), 1);
+///
+/// parser.parse(stream_from_tokens(quote!(a))).unwrap_err();
+/// ```
+#[must_use]
+pub fn skip_group<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Vec<(RustToken, RustSpan)>, Error = E> + Clone {
+    filter(RustToken::is_start_delim)
+        .rewind()
+        .ignore_then(token_tree())
+}
+
+/// A recovery strategy which skips token trees until one of the `sync` parsers would match, or
+/// until the end of the enclosing group. The matching input is left in place, for a separator
+/// parser to consume. Groups are skipped whole, so a `sync` match nested inside one is ignored.
+/// At least one token tree is skipped, so this fails if the input already matches or is at the
+/// end of a group.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::recovery::skip_to_sync;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = skip_to_sync::<Cheap<_, RustSpan>, _, _>(&[punct(','), punct(';')]);
+///
+/// let skipped = parser
+///     .clone()
+///     .then_ignore(punct(';'))
+///     .parse(stream_from_tokens(quote!(f(a, b) + 1;)))
+///     .unwrap();
+/// assert_eq!(skipped.len(), 8);
+///
+/// parser.parse(stream_from_tokens(quote!(, a))).unwrap_err();
+/// ```
+#[must_use]
+pub fn skip_to_sync<E, O, S>(
+    sync: &[S],
+) -> impl Parser<RustToken, Vec<(RustToken, RustSpan)>, Error = E> + Clone
+where
+    E: Error<RustToken, Span = RustSpan> + 'static,
+    O: 'static,
+    S: Parser<RustToken, O, Error = E> + Clone + 'static,
+{
+    let sync = sync
+        .iter()
+        .cloned()
+        .map(|sync| sync.ignored().boxed())
+        .reduce(|sync, other| sync.or(other).boxed())
+        .unwrap_or_else(|| filter(|_: &RustToken| false).ignored().boxed());
+
+    sync.rewind()
+        .or_not()
+        .then(token_tree())
+        .try_map(|(at_sync, tree), span| match at_sync {
+            Some(()) => Err(E::expected_input_found(span, [], Some(tree[0].0.clone()))),
+            None => Ok(tree),
+        })
+        .repeated()
+        .at_least(1)
+        .flatten()
+}
+
+/// A recovery strategy for parsers running inside a group with the given delimiter, which skips
+/// everything up to its closing delimiter. The closing delimiter is left in place, so the group
+/// itself still parses. It fails if the enclosing group has a different delimiter, or if the
+/// input is already at its end.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::recovery::{recover, recover_inside};
+/// # use chumsky::prelude::*;
+/// # use proc_macro2::Delimiter;
+/// # use quote::quote;
+/// let stmts = ident::<RustError>().then_ignore(punct(';')).repeated().then_ignore(end());
+/// let parser = group(Delimiter::Brace, recover(stmts, recover_inside(Delimiter::Brace)));
+///
+/// let (block, errs) = parser.parse_recovery(stream_from_tokens(quote!({ a; b c; d; })));
+/// assert_eq!(block.unwrap().unwrap_err().skipped().tokens().len(), 7);
+/// assert_eq!(errs.len(), 1);
+/// ```
+#[must_use]
+pub fn recover_inside<E: Error<RustToken, Span = RustSpan> + 'static>(
+    delimiter: Delimiter,
+) -> impl Parser<RustToken, Vec<(RustToken, RustSpan)>, Error = E> + Clone {
+    token_tree()
+        .repeated()
+        .at_least(1)
+        .flatten()
+        .then_ignore(just(RustToken::EndDelim(delimiter)).rewind())
+}
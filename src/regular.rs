@@ -4,7 +4,7 @@ use chumsky::Stream;
 use proc_macro2::{Span, TokenStream};
 use std::ops::Range;
 
-use super::{RustSpan, RustToken};
+use super::{RustSpan, RustToken, StreamOrigin};
 use crate::utils::into_vec;
 
 impl chumsky::Span for RustSpan {
@@ -15,7 +15,7 @@ impl chumsky::Span for RustSpan {
         range
             .start
             .join(range.end)
-            .unwrap_or_else(|| Span::mixed_site().into())
+            .unwrap_or_else(|| RustSpan::from(Span::mixed_site()).with_origin(range.start.origin()))
     }
 
     fn context(&self) -> Self::Context {}
@@ -38,3 +38,64 @@ pub fn stream_from_tokens(
 
     Stream::from_iter(Span::mixed_site().into(), tokens.into_iter())
 }
+
+/// Generate a chumsky `Stream` from a Rust `TokenStream`, tagging every span with the stream's
+/// origin. Errors at the end of input point at the last token, or at the macro's call site when
+/// the stream is empty, which for an attribute macro is the attribute itself.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::{stream_with_origin, StreamOrigin};
+/// # use chumsky::prelude::*;
+/// # use quote::quote;
+/// let errs = ident::<RustError>()
+///     .then_ignore(punct('='))
+///     .then(ident())
+///     .parse(stream_with_origin(quote!(method =), StreamOrigin::Args))
+///     .unwrap_err();
+/// assert_eq!(errs[0].span().origin(), StreamOrigin::Args);
+/// ```
+#[must_use]
+pub fn stream_with_origin(
+    stream: TokenStream,
+    origin: StreamOrigin,
+) -> Stream<'static, RustToken, RustSpan, impl Iterator<Item = (RustToken, RustSpan)>> {
+    let tokens = into_vec(stream)
+        .into_iter()
+        .map(|(tok, span)| (tok, span.with_origin(origin)))
+        .collect::<Vec<_>>();
+    let eoi = tokens
+        .last()
+        .map_or_else(|| Span::call_site().into(), |(_, span)| *span);
+
+    Stream::from_iter(eoi.with_origin(origin), tokens.into_iter())
+}
+
+/// Generate a chumsky `Stream` from a compiler `proc_macro::TokenStream`, as passed to a
+/// proc-macro. This is a convenience wrapper, which converts the stream into a `proc_macro2` one
+/// for [`stream_from_tokens`]. The `proc_macro` API is only available while a macro is expanding,
+/// so use [`stream_from_tokens`] in tests.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate proc_macro;
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky::prelude::*;
+/// // In a proc-macro crate, this would be `#[proc_macro]`
+/// fn my_macro(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+///     match ident::<RustError>().parse(stream_from_proc_macro(item)) {
+///         Ok(_) => proc_macro::TokenStream::new(),
+///         Err(errs) => errs.iter().map(RustError::to_proc_macro_error).collect(),
+///     }
+/// }
+/// ```
+#[cfg(feature = "proc-macro")]
+#[must_use]
+pub fn stream_from_proc_macro(
+    stream: proc_macro::TokenStream,
+) -> Stream<'static, RustToken, RustSpan, impl Iterator<Item = (RustToken, RustSpan)>> {
+    stream_from_tokens(stream.into())
+}
@@ -0,0 +1,439 @@
+//! Compiling `macro_rules!` matchers into parsers
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use chumsky::Stream;
+use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
+use std::collections::HashSet;
+
+use super::{
+    flat_token, repetition_suffix, token_matches, Binding, Bindings, Fragment, FragmentKind,
+    RepeatOp,
+};
+use crate::primitive::token_tree;
+use crate::syntax::attr::meta;
+use crate::syntax::expr::ExprGrammar;
+use crate::syntax::item::item;
+use crate::syntax::pat::pat_tokens;
+use crate::syntax::stmt::block;
+use crate::syntax::ty::TypeGrammar;
+use crate::syntax::vis::visibility;
+use crate::syntax::{
+    group_tokens, keyword_token, lifetime_tokens, literal_token, punct_token, token, Tokens,
+    Verbatim,
+};
+use crate::utils::closest_match;
+use crate::{RustError, RustSpan, RustToken};
+
+/// The names bound so far by a matcher, alongside what each part of the matcher captured
+type Captures = Vec<(String, Binding)>;
+
+/// A single part of a compiled matcher
+#[derive(Clone, Debug)]
+enum Elem {
+    /// A token which must appear exactly, including the delimiters of groups
+    Token(RustToken),
+    /// A fragment capturing some syntax, such as `$name:ident`
+    Fragment(String, FragmentKind),
+    /// A repetition, such as `$($x:expr),*`
+    Repeat {
+        /// The parts of the matcher being repeated
+        elems: Vec<Elem>,
+        /// The token separating each repetition, if any
+        separator: Option<RustToken>,
+        /// How many times the repetition may match
+        op: RepeatOp,
+    },
+}
+
+impl Elem {
+    /// Returns whether this part of the matcher can match no tokens at all
+    fn can_be_empty(&self) -> bool {
+        match self {
+            Elem::Token(_) => false,
+            Elem::Fragment(_, kind) => kind.can_be_empty(),
+            Elem::Repeat { elems, op, .. } => {
+                *op != RepeatOp::OneOrMore || elems.iter().all(Elem::can_be_empty)
+            }
+        }
+    }
+
+    /// Add the names bound within this part of the matcher to `names`
+    fn names(&self, names: &mut Vec<String>) {
+        match self {
+            Elem::Token(_) => (),
+            Elem::Fragment(name, _) => names.push(name.clone()),
+            Elem::Repeat { elems, .. } => elems.iter().for_each(|elem| elem.names(names)),
+        }
+    }
+}
+
+/// A `macro_rules!` matcher, such as `$name:ident : $ty:ty $(, $rest:expr)*`, which can be
+/// turned into a parser producing [`Bindings`]
+#[derive(Clone, Debug)]
+pub struct Matcher {
+    /// The parts of the matcher, in order
+    elems: Vec<Elem>,
+}
+
+impl Matcher {
+    /// Compile a matcher, as it would be written on the left-hand side of a `macro_rules!` arm
+    /// without the outer delimiters. Every fragment specifier is supported, and repetitions may
+    /// be nested and have separators.
+    ///
+    /// # Errors
+    ///
+    /// If the matcher is malformed, such as having an unknown fragment specifier, a binding used
+    /// twice, or a repetition without an operator
+    pub fn new(pattern: TokenStream) -> Result<Matcher, Vec<RustError>> {
+        let mut errors = Vec::new();
+        let elems = parse_elems(pattern, &mut HashSet::new(), &mut errors);
+        if errors.is_empty() {
+            Ok(Matcher { elems })
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Get the names bound by this matcher, without the `$`, in the order they appear
+    #[must_use]
+    pub fn names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        self.elems.iter().for_each(|elem| elem.names(&mut names));
+        names
+    }
+
+    /// Build a parser which matches this matcher, binding each fragment. Names inside `n`
+    /// repetitions are bound `n` levels of [`Binding::Repeated`] deep.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky_proc::prelude::*;
+    /// # use chumsky_proc::rules::Matcher;
+    /// # use chumsky::prelude::*;
+    /// # use chumsky::error::Cheap;
+    /// # use quote::{quote, ToTokens};
+    /// let matcher = Matcher::new(quote!($name:ident : $ty:ty $(, $rest:expr)*)).unwrap();
+    /// let parser = matcher.parser::<Cheap<_, RustSpan>>().then_ignore(end());
+    ///
+    /// let bindings = parser
+    ///     .parse(stream_from_tokens(quote!(foo: Vec<u8>, 1 + 2, bar())))
+    ///     .unwrap();
+    /// let ty = bindings.get_single("ty").unwrap();
+    /// assert_eq!(ty.to_token_stream().to_string(), quote!(Vec<u8>).to_string());
+    /// assert_eq!(bindings.get_repeated("rest").unwrap().len(), 2);
+    ///
+    /// parser.parse(stream_from_tokens(quote!(foo: Vec<u8>, 1 +))).unwrap_err();
+    ///
+    /// // Items are captured exactly as written, including their spans
+    /// let input = quote!(fn f<T>() where T: Copy, {});
+    /// let bindings = Matcher::new(quote!($item:item))
+    ///     .unwrap()
+    ///     .parser::<Cheap<_, RustSpan>>()
+    ///     .parse(stream_from_tokens(input.clone()))
+    ///     .unwrap();
+    /// let item = bindings.get_single("item").unwrap();
+    /// assert_eq!(item.to_token_stream().to_string(), input.to_string());
+    ///
+    /// // Malformed matchers are reported when compiling them
+    /// Matcher::new(quote!($name:ident $ty:typ)).unwrap_err();
+    /// Matcher::new(quote!($($x:tt),)).unwrap_err();
+    /// ```
+    #[must_use]
+    pub fn parser<E: Error<RustToken, Span = RustSpan> + 'static>(
+        &self,
+    ) -> impl Parser<RustToken, Bindings, Error = E> + Clone {
+        let grammars = Grammars {
+            types: TypeGrammar::new(),
+            exprs: ExprGrammar::new(),
+        };
+        compile(&self.elems, &grammars).map(|captures| captures.into_iter().collect())
+    }
+}
+
+/// Parse the parts of a matcher, reporting problems to `errors`. `names` holds every name bound
+/// so far, to catch duplicates.
+fn parse_elems(
+    stream: TokenStream,
+    names: &mut HashSet<String>,
+    errors: &mut Vec<RustError>,
+) -> Vec<Elem> {
+    let mut elems = Vec::new();
+    let mut trees = stream.into_iter().peekable();
+
+    while let Some(tree) = trees.next() {
+        match tree {
+            TokenTree::Punct(dollar) if dollar.as_char() == '$' => match trees.next() {
+                Some(TokenTree::Ident(name)) => {
+                    let name_span: RustSpan = name.span().into();
+                    let kind = match (trees.next(), trees.next()) {
+                        (Some(TokenTree::Punct(colon)), Some(TokenTree::Ident(kind)))
+                            if colon.as_char() == ':' =>
+                        {
+                            let kind_name = kind.to_string();
+                            let found = FragmentKind::from_name(&kind_name);
+                            if found.is_none() {
+                                let mut err = RustError::custom(
+                                    kind.span().into(),
+                                    format!("unknown fragment specifier `{}`", kind_name),
+                                );
+                                if let Some(similar) = closest_match(
+                                    &kind_name,
+                                    FragmentKind::ALL.iter().map(|kind| kind.name()),
+                                ) {
+                                    err = err.with_help(format!("did you mean `{}`?", similar));
+                                }
+                                errors.push(err);
+                            }
+                            found
+                        }
+                        _ => {
+                            errors.push(
+                                RustError::custom(
+                                    name_span,
+                                    format!("missing fragment specifier for `${}`", name),
+                                )
+                                .with_help(format!("write it like `${}:ident`", name)),
+                            );
+                            None
+                        }
+                    };
+
+                    let name = name.to_string();
+                    if !names.insert(name.clone()) {
+                        errors.push(RustError::custom(
+                            name_span,
+                            format!("duplicate matcher binding `${}`", name),
+                        ));
+                    }
+                    if let Some(kind) = kind {
+                        elems.push(Elem::Fragment(name, kind));
+                    }
+                }
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                    let span = group.span().into();
+                    let inner = parse_elems(group.stream(), names, errors);
+                    if let Some((separator, op)) = repetition_suffix(&mut trees, span, errors) {
+                        if separator.is_none() && inner.iter().all(Elem::can_be_empty) {
+                            errors.push(RustError::custom(
+                                span,
+                                "this repetition can match no tokens, so would repeat forever",
+                            ));
+                        }
+                        elems.push(Elem::Repeat {
+                            elems: inner,
+                            separator: separator.map(flat_token),
+                            op,
+                        });
+                    }
+                }
+                _ => errors.push(RustError::custom(
+                    dollar.span().into(),
+                    "expected a fragment like `$name:ident` or a repetition like `$(...)*` \
+                     after `$`",
+                )),
+            },
+            TokenTree::Group(group) => {
+                elems.push(Elem::Token(RustToken::StartDelim(group.delimiter())));
+                elems.extend(parse_elems(group.stream(), names, errors));
+                elems.push(Elem::Token(RustToken::EndDelim(group.delimiter())));
+            }
+            tree => elems.push(Elem::Token(flat_token(tree))),
+        }
+    }
+
+    elems
+}
+
+/// The grammars shared by every fragment in a matcher, built once per parser
+struct Grammars<E: Error<RustToken, Span = RustSpan> + 'static> {
+    /// The type grammar, for `ty` and `path` fragments
+    types: TypeGrammar<E>,
+    /// The expression grammar, for `expr` and `stmt` fragments
+    exprs: ExprGrammar<E>,
+}
+
+/// Build a parser for a single fragment, keeping the tokens it matched
+fn fragment<E: Error<RustToken, Span = RustSpan> + 'static>(
+    kind: FragmentKind,
+    grammars: &Grammars<E>,
+) -> BoxedParser<'static, RustToken, Tokens, E> {
+    match kind {
+        FragmentKind::Block => block()
+            .rewind()
+            .ignore_then(group_tokens(Delimiter::Brace))
+            .boxed(),
+        FragmentKind::Expr => grammars.exprs.expr.clone(),
+        FragmentKind::Ident => token(None, |tok| tok.as_ident().is_some_and(|i| i != "_"))
+            .map(|tok| vec![tok])
+            .boxed(),
+        FragmentKind::Item => consumed(item()),
+        FragmentKind::Lifetime => lifetime_tokens().boxed(),
+        FragmentKind::Literal => punct_token('-')
+            .or_not()
+            .chain(
+                literal_token()
+                    .or(keyword_token("true"))
+                    .or(keyword_token("false")),
+            )
+            .boxed(),
+        FragmentKind::Meta => consumed(meta()),
+        FragmentKind::Pat => pat_tokens(true).boxed(),
+        FragmentKind::PatParam => pat_tokens(false).boxed(),
+        FragmentKind::Path => grammars.types.path.clone(),
+        FragmentKind::Stmt => {
+            let local = keyword_token("let")
+                .chain(pat_tokens(true))
+                .chain(
+                    punct_token(':')
+                        .chain(grammars.types.ty.clone())
+                        .or_not()
+                        .flatten(),
+                )
+                .chain(
+                    punct_token('=')
+                        .chain(grammars.exprs.expr.clone())
+                        .or_not()
+                        .flatten(),
+                )
+                .chain(
+                    keyword_token("else")
+                        .chain(group_tokens(Delimiter::Brace))
+                        .or_not()
+                        .flatten(),
+                );
+
+            local
+                .or(grammars.exprs.expr.clone())
+                .or(fragment(FragmentKind::Item, grammars))
+                .boxed()
+        }
+        FragmentKind::Tt => token_tree().boxed(),
+        FragmentKind::Ty => grammars.types.ty.clone(),
+        FragmentKind::Vis => consumed(visibility()),
+    }
+}
+
+/// Run a parser, keeping the input tokens it consumed rather than its output. The parser is first
+/// run over the rest of the enclosing group to find how many tokens it consumes, and if it fails
+/// there, it's run again over the real input to report its errors.
+fn consumed<O, E, P>(parser: P) -> BoxedParser<'static, RustToken, Tokens, E>
+where
+    O: 'static,
+    E: Error<RustToken, Span = RustSpan> + 'static,
+    P: Parser<RustToken, O, Error = E> + Clone + 'static,
+{
+    token_tree()
+        .repeated()
+        .flatten()
+        .rewind()
+        .then_with(move |rest: Tokens| {
+            let len = rest.len();
+            let eoi = rest
+                .last()
+                .map_or_else(|| Span::call_site().into(), |(_, span)| *span);
+            let remaining = parser
+                .clone()
+                .ignore_then(token_tree().repeated().flatten())
+                .parse(Stream::from_iter(eoi, rest.into_iter()));
+            match remaining {
+                Ok(remaining) => any()
+                    .map_with_span(|tok, span| (tok, span))
+                    .repeated()
+                    .exactly(len - remaining.len())
+                    .boxed(),
+                Err(_) => parser.clone().to(Vec::new()).boxed(),
+            }
+        })
+        .boxed()
+}
+
+/// Build a parser for a sequence of matcher parts, collecting what each one captured
+fn compile<E: Error<RustToken, Span = RustSpan> + 'static>(
+    elems: &[Elem],
+    grammars: &Grammars<E>,
+) -> BoxedParser<'static, RustToken, Captures, E> {
+    elems
+        .iter()
+        .fold(empty().to(Vec::new()).boxed(), |seq, elem| {
+            seq.then(compile_elem(elem, grammars))
+                .map(|(mut captures, more)| {
+                    captures.extend(more);
+                    captures
+                })
+                .boxed()
+        })
+}
+
+/// Build a parser for a single matcher part, collecting what it captured
+fn compile_elem<E: Error<RustToken, Span = RustSpan> + 'static>(
+    elem: &Elem,
+    grammars: &Grammars<E>,
+) -> BoxedParser<'static, RustToken, Captures, E> {
+    match elem {
+        Elem::Token(expected) => {
+            let pattern = expected.clone();
+            token(Some(expected.clone()), move |found| {
+                token_matches(&pattern, found)
+            })
+            .to(Vec::new())
+            .boxed()
+        }
+        Elem::Fragment(name, kind) => {
+            let (name, kind) = (name.clone(), *kind);
+            fragment(kind, grammars)
+                .map(move |tokens| {
+                    vec![(
+                        name.clone(),
+                        Binding::Single(Fragment {
+                            kind,
+                            tokens: Verbatim::new(tokens),
+                        }),
+                    )]
+                })
+                .boxed()
+        }
+        Elem::Repeat {
+            elems,
+            separator,
+            op,
+        } => {
+            let inner = compile(elems, grammars);
+            let min = usize::from(*op == RepeatOp::OneOrMore);
+            let iterations = match (separator, op) {
+                (_, RepeatOp::ZeroOrOne) => inner.repeated().at_most(1).boxed(),
+                (Some(separator), _) => {
+                    let pattern = separator.clone();
+                    inner
+                        .separated_by(token(Some(separator.clone()), move |found| {
+                            token_matches(&pattern, found)
+                        }))
+                        .at_least(min)
+                        .boxed()
+                }
+                (None, _) => inner.repeated().at_least(min).boxed(),
+            };
+
+            let mut names = Vec::new();
+            elems.iter().for_each(|elem| elem.names(&mut names));
+            iterations
+                .map(move |iterations| {
+                    let mut columns = names
+                        .iter()
+                        .map(|name| (name.clone(), Vec::new()))
+                        .collect::<Vec<_>>();
+                    for (name, binding) in iterations.into_iter().flatten() {
+                        if let Some((_, column)) = columns.iter_mut().find(|(n, _)| *n == name) {
+                            column.push(binding);
+                        }
+                    }
+                    columns
+                        .into_iter()
+                        .map(|(name, column)| (name, Binding::Repeated(column)))
+                        .collect()
+                })
+                .boxed()
+        }
+    }
+}
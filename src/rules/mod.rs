@@ -0,0 +1,357 @@
+//! Declarative, `macro_rules!`-style matching over Rust tokens
+//!
+//! A [`Matcher`] is compiled from a `macro_rules!` matcher such as `$name:ident $(, $rest:expr)*`
+//! into a chumsky parser, which captures each fragment into a set of [`Bindings`]. A
+//! [`Transcriber`] then expands a template such as `$(let $name = $value;)*` with those bindings.
+
+use proc_macro2::{Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::syntax::Verbatim;
+use crate::utils::lit_eq;
+use crate::{RustError, RustSpan, RustToken};
+
+mod matcher;
+mod transcriber;
+
+pub use matcher::Matcher;
+pub use transcriber::Transcriber;
+
+/// The kind of syntax a fragment captures, such as the `ident` in `$name:ident`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FragmentKind {
+    /// A block, such as `{ a; b }`
+    Block,
+    /// An expression, such as `a + 1`
+    Expr,
+    /// An identifier or keyword, other than `_`
+    Ident,
+    /// An item, such as `fn foo() {}` or `struct Foo;`
+    Item,
+    /// A lifetime, such as `'a`
+    Lifetime,
+    /// A literal, optionally negated, such as `-1` or `"foo"`, or `true` or `false`
+    Literal,
+    /// The contents of an attribute, such as `serde(rename = "x")`
+    Meta,
+    /// A pattern, which may contain top-level alternatives, such as `Some(x) | None`
+    Pat,
+    /// A pattern without top-level alternatives
+    PatParam,
+    /// A path in type position, such as `std::vec::Vec<T>`
+    Path,
+    /// A statement without its trailing `;`, such as `let x = 1` or `foo()`
+    Stmt,
+    /// A single token tree
+    Tt,
+    /// A type, such as `&'a [u8]`
+    Ty,
+    /// A possibly empty visibility, such as `pub(crate)`
+    Vis,
+}
+
+impl FragmentKind {
+    /// Every fragment kind, in the order of their names
+    const ALL: &'static [FragmentKind] = &[
+        FragmentKind::Block,
+        FragmentKind::Expr,
+        FragmentKind::Ident,
+        FragmentKind::Item,
+        FragmentKind::Lifetime,
+        FragmentKind::Literal,
+        FragmentKind::Meta,
+        FragmentKind::Pat,
+        FragmentKind::PatParam,
+        FragmentKind::Path,
+        FragmentKind::Stmt,
+        FragmentKind::Tt,
+        FragmentKind::Ty,
+        FragmentKind::Vis,
+    ];
+
+    /// Get the fragment kind with the given specifier name, such as `"ident"`
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<FragmentKind> {
+        FragmentKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.name() == name)
+    }
+
+    /// Get the specifier name of this fragment kind, such as `"ident"`
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            FragmentKind::Block => "block",
+            FragmentKind::Expr => "expr",
+            FragmentKind::Ident => "ident",
+            FragmentKind::Item => "item",
+            FragmentKind::Lifetime => "lifetime",
+            FragmentKind::Literal => "literal",
+            FragmentKind::Meta => "meta",
+            FragmentKind::Pat => "pat",
+            FragmentKind::PatParam => "pat_param",
+            FragmentKind::Path => "path",
+            FragmentKind::Stmt => "stmt",
+            FragmentKind::Tt => "tt",
+            FragmentKind::Ty => "ty",
+            FragmentKind::Vis => "vis",
+        }
+    }
+
+    /// Returns whether this fragment can match no tokens at all
+    #[must_use]
+    pub fn can_be_empty(self) -> bool {
+        self == FragmentKind::Vis
+    }
+}
+
+impl fmt::Display for FragmentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The operator of a repetition, such as the `*` in `$($x:expr),*`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RepeatOp {
+    /// Zero or more repetitions, `*`
+    ZeroOrMore,
+    /// One or more repetitions, `+`
+    OneOrMore,
+    /// Zero or one repetitions, `?`
+    ZeroOrOne,
+}
+
+impl RepeatOp {
+    /// Get the operator written as the given character, if there is one
+    #[must_use]
+    pub fn from_char(c: char) -> Option<RepeatOp> {
+        match c {
+            '*' => Some(RepeatOp::ZeroOrMore),
+            '+' => Some(RepeatOp::OneOrMore),
+            '?' => Some(RepeatOp::ZeroOrOne),
+            _ => None,
+        }
+    }
+
+    /// Get the character this operator is written as
+    #[must_use]
+    pub fn as_char(self) -> char {
+        match self {
+            RepeatOp::ZeroOrMore => '*',
+            RepeatOp::OneOrMore => '+',
+            RepeatOp::ZeroOrOne => '?',
+        }
+    }
+}
+
+/// The tokens captured by a single fragment, such as `$ty:ty`
+#[derive(Clone, Debug)]
+pub struct Fragment {
+    /// The kind of syntax which was captured
+    pub kind: FragmentKind,
+    /// The captured tokens
+    pub tokens: Verbatim,
+}
+
+impl Fragment {
+    /// Create a fragment from captured tokens
+    #[must_use]
+    pub fn new(kind: FragmentKind, tokens: impl Into<Verbatim>) -> Fragment {
+        Fragment {
+            kind,
+            tokens: tokens.into(),
+        }
+    }
+
+    /// Get the span covering the captured tokens
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        self.tokens.span()
+    }
+}
+
+impl ToTokens for Fragment {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.tokens.to_tokens(tokens);
+    }
+}
+
+/// What a single name in a matcher was bound to
+#[derive(Clone, Debug)]
+pub enum Binding {
+    /// A fragment outside of any repetition
+    Single(Fragment),
+    /// A fragment inside a repetition, with one binding for each time the repetition matched
+    Repeated(Vec<Binding>),
+}
+
+impl Binding {
+    /// Get the captured fragment, if this binding isn't repeated
+    #[must_use]
+    pub fn as_single(&self) -> Option<&Fragment> {
+        match self {
+            Binding::Single(fragment) => Some(fragment),
+            Binding::Repeated(_) => None,
+        }
+    }
+
+    /// Get the binding for each repetition, if this binding is repeated
+    #[must_use]
+    pub fn as_repeated(&self) -> Option<&[Binding]> {
+        match self {
+            Binding::Single(_) => None,
+            Binding::Repeated(bindings) => Some(bindings),
+        }
+    }
+}
+
+/// The fragments captured by a [`Matcher`], by name
+#[derive(Clone, Debug, Default)]
+pub struct Bindings {
+    /// The binding of each name, without the `$`
+    bindings: HashMap<String, Binding>,
+}
+
+impl Bindings {
+    /// Create an empty set of bindings
+    #[must_use]
+    pub fn new() -> Bindings {
+        Bindings::default()
+    }
+
+    /// Bind a name, without the `$`, replacing any previous binding
+    pub fn insert(&mut self, name: impl Into<String>, binding: Binding) {
+        self.bindings.insert(name.into(), binding);
+    }
+
+    /// Get the binding of a name, without the `$`
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Binding> {
+        self.bindings.get(name)
+    }
+
+    /// Get the fragment bound to a name outside of any repetition
+    #[must_use]
+    pub fn get_single(&self, name: &str) -> Option<&Fragment> {
+        self.get(name).and_then(Binding::as_single)
+    }
+
+    /// Get the bindings of a name inside a repetition
+    #[must_use]
+    pub fn get_repeated(&self, name: &str) -> Option<&[Binding]> {
+        self.get(name).and_then(Binding::as_repeated)
+    }
+
+    /// Iterate over every name and its binding, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Binding)> {
+        self.bindings
+            .iter()
+            .map(|(name, binding)| (name.as_str(), binding))
+    }
+
+    /// Get the number of bound names
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.bindings.len()
+    }
+
+    /// Returns whether no names are bound
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+}
+
+impl FromIterator<(String, Binding)> for Bindings {
+    fn from_iter<I: IntoIterator<Item = (String, Binding)>>(iter: I) -> Self {
+        Bindings {
+            bindings: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Convert a token tree which isn't a group into a flat token
+fn flat_token(tree: TokenTree) -> RustToken {
+    match tree {
+        TokenTree::Ident(ident) => RustToken::Ident(ident),
+        TokenTree::Punct(punct) => RustToken::Punct(punct),
+        TokenTree::Literal(lit) => RustToken::Literal(lit),
+        TokenTree::Group(group) => RustToken::StartDelim(group.delimiter()),
+    }
+}
+
+/// Returns whether a token in the input matches one written in a pattern. Punctuation joined in
+/// the pattern must be joined in the input, but the spacing of the last punctuation is ignored.
+fn token_matches(pattern: &RustToken, found: &RustToken) -> bool {
+    match (pattern, found) {
+        (RustToken::Ident(pattern), RustToken::Ident(found)) => pattern == found,
+        (RustToken::Punct(pattern), RustToken::Punct(found)) => {
+            pattern.as_char() == found.as_char()
+                && (pattern.spacing() == Spacing::Alone || found.spacing() == Spacing::Joint)
+        }
+        (RustToken::Literal(pattern), RustToken::Literal(found)) => lit_eq(pattern, found),
+        _ => pattern == found,
+    }
+}
+
+/// Parse the separator and operator following the group of a repetition, such as the `,*` in
+/// `$($x),*`. The `span` is that of the repetition's group, for reporting a missing operator.
+fn repetition_suffix(
+    trees: &mut std::iter::Peekable<impl Iterator<Item = TokenTree>>,
+    span: RustSpan,
+    errors: &mut Vec<RustError>,
+) -> Option<(Option<TokenTree>, RepeatOp)> {
+    let op_of = |tree: Option<&TokenTree>| match tree {
+        Some(TokenTree::Punct(punct)) => RepeatOp::from_char(punct.as_char()),
+        _ => None,
+    };
+
+    if let Some(op) = op_of(trees.peek()) {
+        trees.next();
+        return Some((None, op));
+    }
+
+    let separator = match trees.next() {
+        Some(TokenTree::Group(group)) => {
+            errors.push(RustError::custom(
+                group.span().into(),
+                "a delimited group can't be used as a repetition separator",
+            ));
+            return None;
+        }
+        Some(tree) => tree,
+        None => {
+            errors.push(
+                RustError::custom(span, "expected a repetition operator")
+                    .with_help("add `*`, `+` or `?` after the repetition"),
+            );
+            return None;
+        }
+    };
+
+    let sep_span: RustSpan = separator.span().into();
+    match op_of(trees.peek()) {
+        Some(RepeatOp::ZeroOrOne) => {
+            errors.push(RustError::custom(
+                sep_span,
+                "the `?` repetition operator doesn't take a separator",
+            ));
+            None
+        }
+        Some(op) => {
+            trees.next();
+            Some((Some(separator), op))
+        }
+        None => {
+            errors.push(
+                RustError::custom(sep_span, "expected a repetition operator")
+                    .with_help("add `*`, `+` or `?` after the separator"),
+            );
+            None
+        }
+    }
+}
@@ -0,0 +1,298 @@
+//! Expanding `macro_rules!` transcribers with captured bindings
+
+use proc_macro2::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+
+use super::{repetition_suffix, Binding, Bindings, FragmentKind};
+use crate::utils::closest_match;
+use crate::{RustError, RustSpan};
+
+/// A single part of a transcriber
+#[derive(Clone, Debug)]
+enum Elem {
+    /// A token which is copied to the output as-is
+    Token(TokenTree),
+    /// A delimited group, whose contents are expanded in turn
+    Group(Delimiter, Span, Vec<Elem>),
+    /// A variable, such as `$name`
+    Var(Ident),
+    /// A repetition, such as `$($x),*`
+    Repeat {
+        /// The parts of the transcriber being repeated
+        elems: Vec<Elem>,
+        /// The token placed between each repetition, if any
+        separator: Option<TokenTree>,
+        /// The span of the repetition's group
+        span: RustSpan,
+    },
+}
+
+impl Elem {
+    /// Add the variables used within this part of the transcriber to `vars`
+    fn vars<'a>(&'a self, vars: &mut Vec<&'a Ident>) {
+        match self {
+            Elem::Token(_) => (),
+            Elem::Var(name) => vars.push(name),
+            Elem::Group(_, _, elems) | Elem::Repeat { elems, .. } => {
+                elems.iter().for_each(|elem| elem.vars(vars));
+            }
+        }
+    }
+}
+
+/// A `macro_rules!` transcriber, such as `$(let $name = $value;)*`, which can be expanded with
+/// the [`Bindings`] captured by a [`Matcher`][super::Matcher]
+#[derive(Clone, Debug)]
+pub struct Transcriber {
+    /// The parts of the transcriber, in order
+    elems: Vec<Elem>,
+}
+
+impl Transcriber {
+    /// Compile a transcriber, as it would be written on the right-hand side of a `macro_rules!`
+    /// arm without the outer delimiters
+    ///
+    /// # Errors
+    ///
+    /// If the transcriber is malformed, such as having a `$` not followed by a variable or a
+    /// repetition, or a repetition without an operator
+    pub fn new(template: TokenStream) -> Result<Transcriber, Vec<RustError>> {
+        let mut errors = Vec::new();
+        let elems = parse_elems(template, &mut errors);
+        if errors.is_empty() {
+            Ok(Transcriber { elems })
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Expand this transcriber with the given bindings. Each repetition is expanded once for each
+    /// repetition of the variables inside it, which must all repeat the same number of times.
+    /// Variables bound outside a repetition may be used inside one, and are repeated as needed.
+    ///
+    /// Captured expressions are wrapped in an invisible group, so `$a * 2` keeps meaning
+    /// `($a) * 2` when `$a` is `1 + 1`.
+    ///
+    /// # Errors
+    ///
+    /// If a variable isn't bound, is used inside fewer repetitions than it was captured in, or
+    /// repeats a different number of times than another variable in the same repetition
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use chumsky_proc::prelude::*;
+    /// # use chumsky_proc::rules::{Binding, Bindings, Fragment, FragmentKind, Matcher, Transcriber};
+    /// # use chumsky::prelude::*;
+    /// # use chumsky::error::Cheap;
+    /// # use quote::quote;
+    /// let matcher = Matcher::new(quote!($($name:ident = $value:expr),*)).unwrap();
+    /// let bindings = matcher
+    ///     .parser::<Cheap<_, RustSpan>>()
+    ///     .parse(stream_from_tokens(quote!(a = 1, b = 2)))
+    ///     .unwrap();
+    ///
+    /// let transcriber = Transcriber::new(quote!($(let $name = $value;)*)).unwrap();
+    /// let output = transcriber.expand(&bindings).unwrap();
+    /// assert_eq!(
+    ///     output.to_string().replace(' ', ""),
+    ///     "leta=1;letb=2;",
+    /// );
+    ///
+    /// // Variables in the same repetition must repeat the same number of times
+    /// let mut bindings = Bindings::new();
+    /// let tt = |tokens| Binding::Single(Fragment::new(FragmentKind::Tt, tokens));
+    /// bindings.insert("a", Binding::Repeated(vec![tt(quote!(x)), tt(quote!(y))]));
+    /// bindings.insert("b", Binding::Repeated(vec![tt(quote!(z))]));
+    /// Transcriber::new(quote!($($a $b)*)).unwrap().expand(&bindings).unwrap_err();
+    ///
+    /// // And must be used inside as many repetitions as they were captured in
+    /// Transcriber::new(quote!($a)).unwrap().expand(&bindings).unwrap_err();
+    /// ```
+    pub fn expand(&self, bindings: &Bindings) -> Result<TokenStream, Vec<RustError>> {
+        let mut output = TokenStream::new();
+        let mut errors = Vec::new();
+        expand_elems(
+            &self.elems,
+            bindings,
+            &mut Vec::new(),
+            &mut output,
+            &mut errors,
+        );
+        if errors.is_empty() {
+            Ok(output)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Parse the parts of a transcriber, reporting problems to `errors`
+fn parse_elems(stream: TokenStream, errors: &mut Vec<RustError>) -> Vec<Elem> {
+    let mut elems = Vec::new();
+    let mut trees = stream.into_iter().peekable();
+
+    while let Some(tree) = trees.next() {
+        match tree {
+            TokenTree::Punct(dollar) if dollar.as_char() == '$' => match trees.next() {
+                Some(TokenTree::Ident(name)) => elems.push(Elem::Var(name)),
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                    let span = group.span().into();
+                    let inner = parse_elems(group.stream(), errors);
+                    // The operator only matters when matching, so it's checked but not kept
+                    if let Some((separator, _)) = repetition_suffix(&mut trees, span, errors) {
+                        elems.push(Elem::Repeat {
+                            elems: inner,
+                            separator,
+                            span,
+                        });
+                    }
+                }
+                _ => errors.push(RustError::custom(
+                    dollar.span().into(),
+                    "expected a variable like `$name` or a repetition like `$(...)*` after `$`",
+                )),
+            },
+            TokenTree::Group(group) => elems.push(Elem::Group(
+                group.delimiter(),
+                group.span(),
+                parse_elems(group.stream(), errors),
+            )),
+            tree => elems.push(Elem::Token(tree)),
+        }
+    }
+
+    elems
+}
+
+/// Get what a variable is bound to at the current position within repetitions. Bindings which
+/// repeat fewer times than the current depth stay at their last level.
+fn binding_at<'a>(binding: &'a Binding, indices: &[usize]) -> Option<&'a Binding> {
+    let mut binding = binding;
+    for &index in indices {
+        match binding {
+            Binding::Single(_) => break,
+            Binding::Repeated(bindings) => binding = bindings.get(index)?,
+        }
+    }
+    Some(binding)
+}
+
+/// Report an error for a variable without a binding
+fn unbound(name: &Ident, bindings: &Bindings) -> RustError {
+    let name_str = name.to_string();
+    let err = RustError::custom(
+        name.span().into(),
+        format!("no variable named `${}` was bound", name_str),
+    );
+    match closest_match(&name_str, bindings.iter().map(|(name, _)| name)) {
+        Some(similar) => err.with_help(format!("did you mean `${}`?", similar)),
+        None => err,
+    }
+}
+
+/// Expand a sequence of transcriber parts into `output`. `indices` holds the current iteration of
+/// each repetition being expanded, from the outermost in.
+fn expand_elems(
+    elems: &[Elem],
+    bindings: &Bindings,
+    indices: &mut Vec<usize>,
+    output: &mut TokenStream,
+    errors: &mut Vec<RustError>,
+) {
+    for elem in elems {
+        match elem {
+            Elem::Token(tree) => output.extend([tree.clone()]),
+            Elem::Group(delimiter, span, elems) => {
+                let mut inner = TokenStream::new();
+                expand_elems(elems, bindings, indices, &mut inner, errors);
+                let mut group = Group::new(*delimiter, inner);
+                group.set_span(*span);
+                output.extend([TokenTree::Group(group)]);
+            }
+            Elem::Var(name) => {
+                let Some(binding) = bindings.get(&name.to_string()) else {
+                    errors.push(unbound(name, bindings));
+                    continue;
+                };
+                match binding_at(binding, indices) {
+                    Some(Binding::Single(fragment)) if fragment.kind == FragmentKind::Expr => {
+                        let mut group = Group::new(Delimiter::None, fragment.to_token_stream());
+                        group.set_span(*fragment.span());
+                        output.extend([TokenTree::Group(group)]);
+                    }
+                    Some(Binding::Single(fragment)) => fragment.to_tokens(output),
+                    _ => errors.push(
+                        RustError::custom(
+                            name.span().into(),
+                            format!("variable `${}` is still repeating at this depth", name),
+                        )
+                        .with_help(format!("use it inside a repetition, like `$(${})*`", name)),
+                    ),
+                }
+            }
+            Elem::Repeat {
+                elems,
+                separator,
+                span,
+            } => {
+                let mut vars = Vec::new();
+                elems.iter().for_each(|elem| elem.vars(&mut vars));
+
+                // Every variable repeating at this depth must agree on how many times it repeats
+                let mut count: Option<(&Ident, usize)> = None;
+                let mut failed = false;
+                for name in vars {
+                    let Some(binding) = bindings.get(&name.to_string()) else {
+                        errors.push(unbound(name, bindings));
+                        failed = true;
+                        continue;
+                    };
+                    let Some(Binding::Repeated(repeats)) = binding_at(binding, indices) else {
+                        continue;
+                    };
+                    match count {
+                        None => count = Some((name, repeats.len())),
+                        Some((first, len)) if len != repeats.len() => {
+                            errors.push(RustError::custom(
+                                *span,
+                                format!(
+                                    "variable `${}` repeats {} times, but `${}` repeats {} times",
+                                    first,
+                                    len,
+                                    name,
+                                    repeats.len(),
+                                ),
+                            ));
+                            failed = true;
+                        }
+                        Some(_) => (),
+                    }
+                }
+
+                let Some((_, count)) = count else {
+                    if !failed {
+                        errors.push(RustError::custom(
+                            *span,
+                            "this repetition doesn't contain any variables repeating at this \
+                             depth",
+                        ));
+                    }
+                    continue;
+                };
+                if failed {
+                    continue;
+                }
+
+                for index in 0..count {
+                    if index > 0 {
+                        output.extend(separator.clone());
+                    }
+                    indices.push(index);
+                    expand_elems(elems, bindings, indices, output, errors);
+                    indices.pop();
+                }
+            }
+        }
+    }
+}
@@ -4,26 +4,67 @@ use proc_macro2::Span;
 use std::borrow::Borrow;
 use std::ops::Deref;
 
-/// A wrapper around a Rust `Span`
+/// Which token stream a span came from. Attribute macros receive two streams, and the compiler's
+/// spans alone can't tell them apart.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum StreamOrigin {
+    /// The only input of a function-like or derive macro, or a span from anywhere else
+    #[default]
+    Input,
+    /// The arguments of an attribute macro, inside `#[attr(...)]`
+    Args,
+    /// The item an attribute macro is attached to
+    Item,
+}
+
+/// A wrapper around a Rust `Span`, tagged with the stream it came from
 #[derive(Copy, Clone, Debug)]
-pub struct RustSpan(Span);
+pub struct RustSpan {
+    /// The compiler's span
+    span: Span,
+    /// The stream the span came from
+    origin: StreamOrigin,
+}
 
 impl RustSpan {
-    /// Join this span to another - always returns `None` when not on nightly
+    /// Get the stream this span came from
+    #[must_use]
+    pub fn origin(&self) -> StreamOrigin {
+        self.origin
+    }
+
+    /// Tag this span as coming from the given stream
+    #[must_use]
+    pub fn with_origin(self, origin: StreamOrigin) -> RustSpan {
+        RustSpan { origin, ..self }
+    }
+
+    /// Join this span to another, keeping this span's origin - always returns `None` when not on
+    /// nightly
     pub(crate) fn join(self, other: RustSpan) -> Option<RustSpan> {
-        self.0.join(other.0).map(RustSpan::from)
+        self.span
+            .join(other.span)
+            .map(|span| RustSpan { span, ..self })
+    }
+
+    /// Join this span to another, falling back to this span when joining isn't possible
+    pub(crate) fn join_lossy(self, other: RustSpan) -> RustSpan {
+        self.join(other).unwrap_or(self)
     }
 }
 
 impl From<Span> for RustSpan {
     fn from(span: Span) -> Self {
-        RustSpan(span)
+        RustSpan {
+            span,
+            origin: StreamOrigin::default(),
+        }
     }
 }
 
 impl From<RustSpan> for Span {
     fn from(span: RustSpan) -> Self {
-        span.0
+        span.span
     }
 }
 
@@ -31,18 +72,18 @@ impl Deref for RustSpan {
     type Target = Span;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.span
     }
 }
 
 impl AsRef<Span> for RustSpan {
     fn as_ref(&self) -> &Span {
-        &self.0
+        &self.span
     }
 }
 
 impl Borrow<Span> for RustSpan {
     fn borrow(&self) -> &Span {
-        &self.0
+        &self.span
     }
 }
@@ -0,0 +1,267 @@
+//! Interoperability with `syn`, so grammars can move to chumsky one piece at a time
+//!
+//! [`syn_parse`] embeds any [`syn::parse::Parse`] type, such as [`syn::Type`], inside a chumsky
+//! parser. In the other direction, [`parse_with_chumsky`] and [`ChumskyParse`] run chumsky parsers
+//! from `syn`, so they slot into `parse_macro_input!`, `Attribute::parse_args_with` and existing
+//! `syn`-based derives.
+
+use chumsky::error::{Cheap, Error, Simple, SimpleReason};
+use chumsky::prelude::*;
+use chumsky::Stream;
+use proc_macro2::{TokenStream, TokenTree};
+use syn::parse::{ParseStream, Parser as _};
+
+use crate::error::{describe, unexpected_message};
+use crate::parse::Parse;
+use crate::primitive::token_tree;
+use crate::utils::{from_slice, into_vec};
+use crate::{RustError, RustSpan, RustToken};
+
+/// An error type which `syn` errors can be converted into
+pub trait FromSynError: Error<RustToken, Span = RustSpan> {
+    /// Convert a `syn` error, keeping its span
+    fn from_syn_error(error: syn::Error) -> Self;
+}
+
+impl FromSynError for RustError {
+    /// Keeps the first message at its span. Any further messages become help notes.
+    fn from_syn_error(error: syn::Error) -> Self {
+        let mut errors = error.into_iter();
+        let first = errors.next().expect("syn errors always have a message");
+        errors.fold(
+            RustError::custom(first.span().into(), first),
+            RustError::with_help,
+        )
+    }
+}
+
+impl FromSynError for Simple<RustToken, RustSpan> {
+    fn from_syn_error(error: syn::Error) -> Self {
+        Simple::custom(error.span().into(), error)
+    }
+}
+
+impl FromSynError for Cheap<RustToken, RustSpan> {
+    fn from_syn_error(error: syn::Error) -> Self {
+        Cheap::expected_input_found(error.span().into(), [], None)
+    }
+}
+
+/// An error type which can be converted into a `syn` error
+pub trait IntoSynError: Error<RustToken, Span = RustSpan> {
+    /// Convert into a `syn` error at the same span
+    fn into_syn_error(self) -> syn::Error;
+}
+
+impl IntoSynError for RustError {
+    fn into_syn_error(self) -> syn::Error {
+        syn::Error::new(*self.span(), self)
+    }
+}
+
+impl IntoSynError for Simple<RustToken, RustSpan> {
+    fn into_syn_error(self) -> syn::Error {
+        let message = match self.reason() {
+            SimpleReason::Custom(message) => message.clone(),
+            SimpleReason::Unclosed { delimiter, .. } => {
+                format!("unclosed delimiter {}", describe(Some(delimiter)))
+            }
+            SimpleReason::Unexpected => {
+                unexpected_message(self.found(), self.label(), self.expected())
+            }
+        };
+        syn::Error::new(*self.span(), message)
+    }
+}
+
+impl IntoSynError for Cheap<RustToken, RustSpan> {
+    fn into_syn_error(self) -> syn::Error {
+        syn::Error::new(*self.span(), "unexpected input")
+    }
+}
+
+/// Drains the rest of a `syn` [`ParseStream`] and runs a chumsky parser over it. All of the
+/// parser's errors are combined into one `syn::Error`. The end of input is at the close of the
+/// enclosing group, or the call site at the top level.
+///
+/// # Errors
+///
+/// If the parser fails, or the stream can't be drained
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky::prelude::*;
+/// # use quote::quote;
+/// # use syn::parse::{ParseStream, Parser as _};
+/// let attr: syn::Attribute = syn::parse_quote!(#[route(get, post)]);
+/// let methods = attr
+///     .parse_args_with(|input: ParseStream<'_>| {
+///         parse_with_chumsky(input, ident::<RustError>().separated_by(punct(',')).then_ignore(end()))
+///     })
+///     .unwrap();
+/// assert_eq!(methods.len(), 2);
+///
+/// let parse = |input: ParseStream<'_>| parse_with_chumsky(input, keyword::<RustError>("get"));
+/// let err = parse.parse2(quote!(put)).unwrap_err();
+/// assert_eq!(err.to_string(), "unexpected `put`, expected `get`");
+/// ```
+pub fn parse_with_chumsky<P, O>(input: ParseStream<'_>, parser: P) -> syn::Result<O>
+where
+    P: Parser<RustToken, O>,
+    P::Error: IntoSynError,
+{
+    let tokens = input.parse::<TokenStream>()?;
+    let eoi = input.span();
+
+    parser
+        .parse(Stream::from_iter(eoi.into(), into_vec(tokens).into_iter()))
+        .map_err(|errors| {
+            errors
+                .into_iter()
+                .map(IntoSynError::into_syn_error)
+                .reduce(|mut combined, error| {
+                    combined.combine(error);
+                    combined
+                })
+                .unwrap_or_else(|| syn::Error::new(eoi, "failed to parse"))
+        })
+}
+
+/// Implements `syn`'s [`Parse`][syn::parse::Parse] for any type implementing ours, so it can be
+/// used with `parse_macro_input!`, `syn::parse2` and anything else expecting a `syn` type. The
+/// whole input must be consumed, and errors are reported as [`RustError`]s.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::Type;
+/// # use proc_macro2::Ident;
+/// # use quote::quote;
+/// #[derive(Parse)]
+/// struct Field {
+///     name: Ident,
+///     #[punct(':')]
+///     ty: Type,
+/// }
+///
+/// let ChumskyParse(field) = syn::parse2::<ChumskyParse<Field>>(quote!(x: u8)).unwrap();
+/// assert_eq!(field.name, "x");
+///
+/// assert!(syn::parse2::<ChumskyParse<Field>>(quote!(x: u8 y)).is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct ChumskyParse<T>(pub T);
+
+impl<T> ChumskyParse<T> {
+    /// Get the parsed value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Parse> syn::parse::Parse for ChumskyParse<T> {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        parse_with_chumsky(input, T::parser::<RustError>().then_ignore(end())).map(ChumskyParse)
+    }
+}
+
+/// Accepts whatever `syn` parses as `T` from the start of the remaining input, which is usually
+/// the longest prefix it can, such as a whole `syn::Expr`. Exactly the tokens `syn` used are
+/// consumed, and `syn` errors are converted with their span. The input ends at the close of any
+/// enclosing group.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::doc::stream_with_docs;
+/// # use chumsky::prelude::*;
+/// # use quote::quote;
+/// let parser = syn_parse::<syn::Type, RustError>()
+///     .then_ignore(punct('='))
+///     .then(syn_parse::<syn::Expr, _>())
+///     .then_ignore(end());
+///
+/// let (ty, expr) = parser
+///     .parse(stream_from_tokens(quote!(Vec<u8> = vec![1, 2] + x)))
+///     .unwrap();
+/// assert!(matches!(ty, syn::Type::Path(_)));
+/// assert!(matches!(expr, syn::Expr::Binary(_)));
+///
+/// // syn's own error message is kept
+/// let errs = parser.parse(stream_from_tokens(quote!(= 1))).err().unwrap();
+/// assert!(errs[0].to_string().starts_with("expected one of"));
+///
+/// // A doc comment is one token here, but several once handed to syn
+/// let (expr, rest) = syn_parse::<syn::Expr, RustError>()
+///     .then(any().repeated())
+///     .parse(stream_with_docs(quote!(x #[doc = " doc"])))
+///     .unwrap();
+/// assert!(matches!(expr, syn::Expr::Path(_)));
+/// assert!(matches!(rest[..], [RustToken::DocComment(_)]));
+/// ```
+#[must_use]
+pub fn syn_parse<T, E>() -> impl Parser<RustToken, T, Error = E> + Clone
+where
+    T: syn::parse::Parse + Clone + 'static,
+    E: FromSynError + 'static,
+{
+    token_tree()
+        .repeated()
+        .flatten()
+        .rewind()
+        .try_map(|tokens: Vec<(RustToken, RustSpan)>, _| {
+            let stream = from_slice(&tokens);
+            let trees = stream.clone().into_iter().count();
+            let (value, rest) = (|input: ParseStream<'_>| {
+                let value = input.parse::<T>()?;
+                let mut rest = 0;
+                while !input.is_empty() {
+                    input.parse::<TokenTree>()?;
+                    rest += 1;
+                }
+                Ok((value, rest))
+            })
+            .parse2(stream)
+            .map_err(E::from_syn_error)?;
+            Ok((value, flat_len(&tokens, trees.saturating_sub(rest))))
+        })
+        .then_with(|(value, len)| any().repeated().exactly(len).to(value))
+}
+
+/// Get the number of flat tokens making up the first `trees` token trees of `tokens`. A doc
+/// comment is a single flat token but several trees, such as `#` and `[doc = "..."]`, so the
+/// counts differ. A doc comment which is only partly covered isn't counted.
+fn flat_len(tokens: &[(RustToken, RustSpan)], mut trees: usize) -> usize {
+    let mut len = 0;
+    while trees > 0 && len < tokens.len() {
+        let (tok_len, tok_trees) = match &tokens[len].0 {
+            RustToken::StartDelim(_) => {
+                let mut depth = 0usize;
+                let end = tokens[len..]
+                    .iter()
+                    .position(|(tok, _)| {
+                        match tok {
+                            RustToken::StartDelim(_) => depth += 1,
+                            RustToken::EndDelim(_) => depth -= 1,
+                            _ => {}
+                        }
+                        depth == 0
+                    })
+                    .expect("Unbalanced delimiters in token slice");
+                (end + 1, 1)
+            }
+            RustToken::DocComment(_) => (1, from_slice(&tokens[len..=len]).into_iter().count()),
+            _ => (1, 1),
+        };
+        if tok_trees > trees {
+            break;
+        }
+        len += tok_len;
+        trees -= tok_trees;
+    }
+    len
+}
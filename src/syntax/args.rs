@@ -0,0 +1,431 @@
+//! Declarative schemas for attribute arguments, such as `#[my_derive(rename = "x", skip)]`
+//!
+//! An [`AttrArgs`] lists the keys an attribute accepts, what kind of value each one takes, and
+//! whether it's required or has a default. Parsing with it reports duplicate, unknown and
+//! missing keys as spanned [`RustError`]s.
+
+use chumsky::prelude::*;
+use chumsky::Stream;
+use std::collections::HashMap;
+
+use super::attr::{Attribute, Meta, MetaList, NestedMeta};
+use super::path::{path, Path};
+use crate::primitive::punct;
+use crate::utils::{closest_match, parse_int, unescape_str};
+use crate::{RustError, RustSpan, RustToken};
+
+/// The kind of value an attribute argument accepts
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ArgKind {
+    /// A string literal, such as `rename = "x"`
+    Str,
+    /// An integer literal, such as `limit = 10` or `offset = -1`
+    Int,
+    /// A flag, either bare such as `skip`, or with a boolean such as `skip = false`
+    Flag,
+    /// A path, such as `with = my::module`
+    Path,
+    /// A nested list, such as `bound(T: Clone)`
+    List,
+}
+
+impl ArgKind {
+    /// Describe what this kind of argument looks like, for error messages
+    fn describe(self, name: &str) -> String {
+        match self {
+            ArgKind::Str => format!("`{} = \"...\"`", name),
+            ArgKind::Int => format!("`{} = <integer>`", name),
+            ArgKind::Flag => format!("`{}` or `{} = <bool>`", name, name),
+            ArgKind::Path => format!("`{} = <path>`", name),
+            ArgKind::List => format!("`{}(...)`", name),
+        }
+    }
+}
+
+/// The value of a parsed attribute argument
+#[derive(Clone, Debug)]
+pub enum ArgValue {
+    /// The unescaped value of a string literal
+    Str(String),
+    /// The value of an integer literal
+    Int(i128),
+    /// Whether a flag is set
+    Flag(bool),
+    /// A path
+    Path(Path),
+    /// A nested list, kept unparsed
+    List(MetaList),
+}
+
+/// A single key accepted by an [`AttrArgs`] schema
+#[derive(Clone, Debug)]
+pub struct Arg {
+    /// The name of the key
+    name: String,
+    /// The kind of value the key accepts
+    kind: ArgKind,
+    /// Whether the key must be provided
+    required: bool,
+    /// The value to use if the key isn't provided
+    default: Option<ArgValue>,
+}
+
+impl Arg {
+    /// Create a new optional key accepting the given kind of value
+    #[must_use]
+    pub fn new(name: impl Into<String>, kind: ArgKind) -> Arg {
+        Arg {
+            name: name.into(),
+            kind,
+            required: false,
+            default: None,
+        }
+    }
+
+    /// Create a new key accepting a string literal
+    #[must_use]
+    pub fn str(name: impl Into<String>) -> Arg {
+        Arg::new(name, ArgKind::Str)
+    }
+
+    /// Create a new key accepting an integer literal
+    #[must_use]
+    pub fn int(name: impl Into<String>) -> Arg {
+        Arg::new(name, ArgKind::Int)
+    }
+
+    /// Create a new flag, which defaults to unset
+    #[must_use]
+    pub fn flag(name: impl Into<String>) -> Arg {
+        Arg::new(name, ArgKind::Flag).default(ArgValue::Flag(false))
+    }
+
+    /// Create a new key accepting a path
+    #[must_use]
+    pub fn path(name: impl Into<String>) -> Arg {
+        Arg::new(name, ArgKind::Path)
+    }
+
+    /// Create a new key accepting a nested list
+    #[must_use]
+    pub fn list(name: impl Into<String>) -> Arg {
+        Arg::new(name, ArgKind::List)
+    }
+
+    /// Make this key required, reporting an error if it's missing. This removes any default, so a
+    /// required flag must be given explicitly.
+    #[must_use]
+    pub fn required(mut self) -> Arg {
+        self.required = true;
+        self.default = None;
+        self
+    }
+
+    /// Set the value to use if this key isn't provided. This makes the key optional again.
+    #[must_use]
+    pub fn default(mut self, value: ArgValue) -> Arg {
+        self.required = false;
+        self.default = Some(value);
+        self
+    }
+
+    /// Convert a nested meta into a value for this key, or return the span of the offending
+    /// tokens
+    fn value_of(&self, meta: &Meta) -> Result<ArgValue, RustSpan> {
+        let (value, span) = match (self.kind, meta) {
+            (ArgKind::Flag, Meta::Path(_)) => (Some(ArgValue::Flag(true)), meta.span()),
+            (ArgKind::List, Meta::List(list)) => (Some(ArgValue::List(list.clone())), meta.span()),
+            (kind, Meta::NameValue(name_value)) => {
+                let span = name_value.value.span();
+                let value = match (kind, name_value.value.tokens()) {
+                    (ArgKind::Str, [(RustToken::Literal(lit), _)]) => {
+                        unescape_str(lit).map(ArgValue::Str)
+                    }
+                    (ArgKind::Int, [(RustToken::Literal(lit), _)]) => {
+                        parse_int(lit).map(ArgValue::Int)
+                    }
+                    (
+                        ArgKind::Int,
+                        [(RustToken::Punct(minus), _), (RustToken::Literal(lit), _)],
+                    ) if minus.as_char() == '-' => parse_int(lit).map(|int| ArgValue::Int(-int)),
+                    (ArgKind::Flag, [(RustToken::Ident(ident), _)]) if ident == "true" => {
+                        Some(ArgValue::Flag(true))
+                    }
+                    (ArgKind::Flag, [(RustToken::Ident(ident), _)]) if ident == "false" => {
+                        Some(ArgValue::Flag(false))
+                    }
+                    (ArgKind::Path, tokens) => path::<RustError>()
+                        .then_ignore(end())
+                        .parse(Stream::from_iter(span, tokens.iter().cloned()))
+                        .ok()
+                        .map(ArgValue::Path),
+                    _ => None,
+                };
+                (value, span)
+            }
+            _ => (None, meta.span()),
+        };
+
+        value.ok_or(span)
+    }
+}
+
+/// A schema for the arguments of an attribute, built from the [`Arg`]s it accepts
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::args::{Arg, ArgValue, AttrArgs};
+/// # use chumsky_proc::syntax::attr::outer_attr;
+/// # use chumsky::prelude::*;
+/// # use quote::quote;
+/// let schema = AttrArgs::new()
+///     .arg(Arg::str("rename"))
+///     .arg(Arg::flag("skip"))
+///     .arg(Arg::int("limit").default(ArgValue::Int(10)))
+///     .arg(Arg::path("with").required());
+///
+/// let attr = outer_attr::<RustError>()
+///     .parse(stream_from_tokens(quote!(#[my_derive(rename = "x", skip, with = a::b)])))
+///     .unwrap();
+/// let args = schema.parse_attr(&attr).unwrap();
+/// assert_eq!(args.get_str("rename"), Some("x"));
+/// assert!(args.flag("skip"));
+/// assert_eq!(args.get_int("limit"), Some(10));
+///
+/// let attr = outer_attr::<RustError>()
+///     .parse(stream_from_tokens(quote!(#[my_derive(renam = "x", skip, skip)])))
+///     .unwrap();
+/// let errors = schema.parse_attr(&attr).unwrap_err();
+/// assert_eq!(errors.len(), 3);
+/// assert_eq!(errors[0].to_string(), "unknown argument `renam`\nhelp: did you mean `rename`?");
+/// assert_eq!(
+///     errors[1].to_string(),
+///     "duplicate argument `skip`\nnote: first given here",
+/// );
+/// assert_eq!(errors[2].to_string(), "missing required argument `with`");
+///
+/// let attr = outer_attr::<RustError>()
+///     .parse(stream_from_tokens(quote!(#[my_derive(with = 1, with = a)])))
+///     .unwrap();
+/// let errors = schema.parse_attr(&attr).unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[0].to_string(), "expected `with = <path>`");
+/// assert_eq!(errors[1].message(), "duplicate argument `with`");
+///
+/// // A required flag has no default, so it must be given
+/// let schema = AttrArgs::new().arg(Arg::flag("inline").required());
+/// let attr = outer_attr::<RustError>()
+///     .parse(stream_from_tokens(quote!(#[my_derive])))
+///     .unwrap();
+/// let errors = schema.parse_attr(&attr).unwrap_err();
+/// assert_eq!(errors[0].to_string(), "missing required argument `inline`");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AttrArgs {
+    /// The keys this schema accepts
+    args: Vec<Arg>,
+}
+
+impl AttrArgs {
+    /// Create a new schema accepting no keys
+    #[must_use]
+    pub fn new() -> AttrArgs {
+        AttrArgs::default()
+    }
+
+    /// Add a key to this schema
+    #[must_use]
+    pub fn arg(mut self, arg: Arg) -> AttrArgs {
+        self.args.push(arg);
+        self
+    }
+
+    /// Get a parser for comma-separated arguments matching this schema, such as the contents of
+    /// the parentheses in `#[my_derive(rename = "x", skip)]`. All problems with the arguments are
+    /// reported, not just the first.
+    #[must_use]
+    pub fn parser(&self) -> impl Parser<RustToken, ParsedArgs, Error = RustError> + Clone {
+        let schema = self.clone();
+        super::attr::nested_meta()
+            .separated_by(punct(','))
+            .allow_trailing()
+            .validate(move |items, span, emit| schema.check(items, span, emit))
+    }
+
+    /// Parse the contents of a list against this schema
+    ///
+    /// # Errors
+    ///
+    /// If the contents aren't valid arguments, or don't match this schema
+    pub fn parse_list(&self, list: &MetaList) -> Result<ParsedArgs, Vec<RustError>> {
+        list.parse_args_with(self.parser())
+    }
+
+    /// Parse the arguments of an attribute against this schema. An attribute with no list, such
+    /// as `#[my_derive]`, is treated as having no arguments.
+    ///
+    /// # Errors
+    ///
+    /// If the attribute is of the form `#[path = value]`, or its arguments don't match this schema
+    pub fn parse_attr(&self, attr: &Attribute) -> Result<ParsedArgs, Vec<RustError>> {
+        match &attr.meta {
+            Meta::Path(path) => {
+                let mut errors = Vec::new();
+                let args = self.check(Vec::new(), path.span(), &mut |err| errors.push(err));
+                if errors.is_empty() {
+                    Ok(args)
+                } else {
+                    Err(errors)
+                }
+            }
+            Meta::List(list) => self.parse_list(list),
+            Meta::NameValue(name_value) => Err(vec![RustError::custom(
+                name_value.eq_token,
+                format!("expected `{}(...)`", name_value.path),
+            )]),
+        }
+    }
+
+    /// Check parsed items against this schema, reporting every problem found
+    fn check(
+        &self,
+        items: Vec<NestedMeta>,
+        span: RustSpan,
+        emit: &mut dyn FnMut(RustError),
+    ) -> ParsedArgs {
+        let mut values = HashMap::new();
+        // The span of each key which was provided, even with an invalid value
+        let mut seen = HashMap::new();
+
+        for item in items {
+            let meta = match item {
+                NestedMeta::Meta(meta) => meta,
+                NestedMeta::Lit(lit) => {
+                    emit(RustError::custom(
+                        lit.span().into(),
+                        "expected an argument, found a literal",
+                    ));
+                    continue;
+                }
+            };
+
+            let name = meta.path().to_string();
+            let arg = match self.args.iter().find(|arg| arg.name == name) {
+                Some(arg) => arg,
+                None => {
+                    let mut err = RustError::custom(
+                        meta.path().span(),
+                        format!("unknown argument `{}`", name),
+                    );
+                    if let Some(similar) =
+                        closest_match(&name, self.args.iter().map(|arg| &*arg.name))
+                    {
+                        err = err.with_help(format!("did you mean `{}`?", similar));
+                    }
+                    emit(err);
+                    continue;
+                }
+            };
+
+            if let Some(first) = seen.get(&name) {
+                emit(
+                    RustError::custom(meta.path().span(), format!("duplicate argument `{}`", name))
+                        .with_secondary(*first, "first given here"),
+                );
+                continue;
+            }
+            seen.insert(name.clone(), meta.path().span());
+
+            match arg.value_of(&meta) {
+                Ok(value) => {
+                    values.insert(name, (value, Some(meta.span())));
+                }
+                Err(span) => emit(RustError::custom(
+                    span,
+                    format!("expected {}", arg.kind.describe(&arg.name)),
+                )),
+            }
+        }
+
+        for arg in &self.args {
+            if seen.contains_key(&arg.name) {
+                continue;
+            }
+            if let Some(default) = &arg.default {
+                values.insert(arg.name.clone(), (default.clone(), None));
+            } else if arg.required {
+                emit(RustError::custom(
+                    span,
+                    format!("missing required argument `{}`", arg.name),
+                ));
+            }
+        }
+
+        ParsedArgs { values }
+    }
+}
+
+/// Arguments parsed by an [`AttrArgs`] schema, including defaults for missing keys
+#[derive(Clone, Debug, Default)]
+pub struct ParsedArgs {
+    /// The value of each key, and its span if it was provided rather than defaulted
+    values: HashMap<String, (ArgValue, Option<RustSpan>)>,
+}
+
+impl ParsedArgs {
+    /// Get the value of a key, if it was provided or has a default
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&ArgValue> {
+        self.values.get(name).map(|(value, _)| value)
+    }
+
+    /// Get the span of a key and its value, if it was provided rather than defaulted
+    #[must_use]
+    pub fn span(&self, name: &str) -> Option<RustSpan> {
+        self.values.get(name).and_then(|(_, span)| *span)
+    }
+
+    /// Get the value of a string key
+    #[must_use]
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        match self.get(name)? {
+            ArgValue::Str(str) => Some(str),
+            _ => None,
+        }
+    }
+
+    /// Get the value of an integer key
+    #[must_use]
+    pub fn get_int(&self, name: &str) -> Option<i128> {
+        match self.get(name)? {
+            ArgValue::Int(int) => Some(*int),
+            _ => None,
+        }
+    }
+
+    /// Returns whether a flag is set
+    #[must_use]
+    pub fn flag(&self, name: &str) -> bool {
+        matches!(self.get(name), Some(ArgValue::Flag(true)))
+    }
+
+    /// Get the value of a path key
+    #[must_use]
+    pub fn get_path(&self, name: &str) -> Option<&Path> {
+        match self.get(name)? {
+            ArgValue::Path(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Get the value of a nested list key
+    #[must_use]
+    pub fn get_list(&self, name: &str) -> Option<&MetaList> {
+        match self.get(name)? {
+            ArgValue::List(list) => Some(list),
+            _ => None,
+        }
+    }
+}
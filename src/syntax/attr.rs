@@ -0,0 +1,428 @@
+//! Outer and inner attributes, such as `#[my_derive(rename = "x", skip)]` or `#![no_std]`
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use chumsky::Stream;
+use proc_macro2::{Delimiter, Group, Literal, Punct, Spacing, TokenStream};
+use quote::ToTokens;
+
+use super::expr::expr;
+use super::path::{path, Path};
+use super::{Expr, Verbatim};
+use crate::primitive::{punct, token_tree};
+use crate::{RustSpan, RustToken};
+
+/// Whether an attribute applies to the following item, or the enclosing one
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AttrStyle {
+    /// An outer attribute, such as `#[derive(Debug)]`
+    Outer,
+    /// An inner attribute, such as `#![no_std]`
+    Inner,
+}
+
+/// An attribute, such as `#[my_derive(rename = "x")]`
+#[derive(Clone, Debug)]
+pub struct Attribute {
+    /// Whether this is an outer or inner attribute
+    pub style: AttrStyle,
+    /// The span of the leading `#`
+    pub pound_token: RustSpan,
+    /// The span of the `!`, for an inner attribute
+    pub bang_token: Option<RustSpan>,
+    /// The span of the opening `[`
+    pub bracket_token: RustSpan,
+    /// The span of the closing `]`
+    pub close_token: RustSpan,
+    /// The contents of the attribute
+    pub meta: Meta,
+}
+
+impl Attribute {
+    /// Get the path of this attribute, such as `my_derive` in `#[my_derive(skip)]`
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.meta.path()
+    }
+
+    /// Returns whether this attribute's path is spelled the same as the provided one
+    #[must_use]
+    pub fn path_is(&self, path: &str) -> bool {
+        self.path().is(path)
+    }
+
+    /// Get the span covering this whole attribute, from the `#` to the `]`
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        self.pound_token.join_lossy(self.close_token)
+    }
+}
+
+impl ToTokens for Attribute {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let mut pound = Punct::new('#', Spacing::Alone);
+        pound.set_span(*self.pound_token);
+        pound.to_tokens(tokens);
+        if let Some(bang_token) = self.bang_token {
+            let mut bang = Punct::new('!', Spacing::Alone);
+            bang.set_span(*bang_token);
+            bang.to_tokens(tokens);
+        }
+        let mut group = Group::new(Delimiter::Bracket, self.meta.to_token_stream());
+        group.set_span(*self.bracket_token.join_lossy(self.close_token));
+        group.to_tokens(tokens);
+    }
+}
+
+/// Iterate over the attributes with the provided path, such as all `#[my_derive(...)]`
+/// attributes on an item
+pub fn filter_attrs<'a>(
+    attrs: &'a [Attribute],
+    path: &'a str,
+) -> impl Iterator<Item = &'a Attribute> + 'a {
+    attrs.iter().filter(move |attr| attr.path_is(path))
+}
+
+/// The contents of an attribute, or of an item in a nested attribute list
+#[derive(Clone, Debug)]
+pub enum Meta {
+    /// A bare path, such as `skip`
+    Path(Path),
+    /// A path followed by a delimited list, such as `nested(a, b)`
+    List(MetaList),
+    /// A path followed by a value, such as `rename = "x"`
+    NameValue(MetaNameValue),
+}
+
+impl Meta {
+    /// Get the path at the start of this meta
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        match self {
+            Meta::Path(path) => path,
+            Meta::List(list) => &list.path,
+            Meta::NameValue(name_value) => &name_value.path,
+        }
+    }
+
+    /// Get the span covering this whole meta
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        match self {
+            Meta::Path(path) => path.span(),
+            Meta::List(list) => list.span(),
+            Meta::NameValue(name_value) => name_value.span(),
+        }
+    }
+}
+
+impl ToTokens for Meta {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Meta::Path(path) => path.to_tokens(tokens),
+            Meta::List(list) => list.to_tokens(tokens),
+            Meta::NameValue(name_value) => name_value.to_tokens(tokens),
+        }
+    }
+}
+
+/// A path followed by a delimited list, such as `nested(a, b)`. The contents are kept as raw
+/// tokens, as not every attribute uses comma-separated metas - use [`MetaList::parse_nested`]
+/// for those that do.
+#[derive(Clone, Debug)]
+pub struct MetaList {
+    /// The path before the list
+    pub path: Path,
+    /// The delimiter around the list
+    pub delimiter: Delimiter,
+    /// The span of the opening delimiter
+    pub open_token: RustSpan,
+    /// The span of the closing delimiter
+    pub close_token: RustSpan,
+    /// The tokens inside the delimiters
+    pub tokens: Verbatim,
+}
+
+impl MetaList {
+    /// Get the span covering this whole list, from the path to the closing delimiter
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        self.path.span().join_lossy(self.close_token)
+    }
+
+    /// Run a parser over the contents of this list, which must consume all of them. Running out
+    /// of input is reported at the closing delimiter.
+    ///
+    /// # Errors
+    ///
+    /// If the parser fails, or doesn't consume the whole list
+    pub fn parse_args_with<O, P>(&self, parser: P) -> Result<O, Vec<P::Error>>
+    where
+        P: Parser<RustToken, O>,
+        P::Error: Error<RustToken, Span = RustSpan>,
+    {
+        parser.then_ignore(end()).parse(Stream::from_iter(
+            self.close_token,
+            self.tokens.tokens().to_vec().into_iter(),
+        ))
+    }
+
+    /// Parse the contents of this list as comma-separated metas or literals, such as
+    /// `rename = "x", skip, nested(a, b)`
+    ///
+    /// # Errors
+    ///
+    /// If the contents aren't a comma-separated list of metas and literals
+    pub fn parse_nested<E: Error<RustToken, Span = RustSpan> + 'static>(
+        &self,
+    ) -> Result<Vec<NestedMeta>, Vec<E>> {
+        self.parse_args_with(nested_meta().separated_by(punct(',')).allow_trailing())
+    }
+}
+
+impl ToTokens for MetaList {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.path.to_tokens(tokens);
+        let mut group = Group::new(self.delimiter, self.tokens.to_token_stream());
+        group.set_span(*self.open_token.join_lossy(self.close_token));
+        group.to_tokens(tokens);
+    }
+}
+
+/// A path followed by a value, such as `rename = "x"`
+#[derive(Clone, Debug)]
+pub struct MetaNameValue {
+    /// The path before the `=`
+    pub path: Path,
+    /// The span of the `=`
+    pub eq_token: RustSpan,
+    /// The value after the `=`
+    pub value: Expr,
+}
+
+impl MetaNameValue {
+    /// Get the span covering this whole meta, from the path to the end of the value
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        self.path.span().join_lossy(self.value.span())
+    }
+
+    /// Get the value as a literal, if it's a single literal token
+    #[must_use]
+    pub fn lit(&self) -> Option<&Literal> {
+        match self.value.tokens() {
+            [(RustToken::Literal(lit), _)] => Some(lit),
+            _ => None,
+        }
+    }
+}
+
+impl ToTokens for MetaNameValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.path.to_tokens(tokens);
+        let mut eq = Punct::new('=', Spacing::Alone);
+        eq.set_span(*self.eq_token);
+        eq.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+/// An item in a nested attribute list
+#[derive(Clone, Debug)]
+pub enum NestedMeta {
+    /// A meta, such as `skip` or `rename = "x"`
+    Meta(Meta),
+    /// A bare literal, such as `"x"`
+    Lit(Literal),
+}
+
+impl NestedMeta {
+    /// Get the span covering this item
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        match self {
+            NestedMeta::Meta(meta) => meta.span(),
+            NestedMeta::Lit(lit) => lit.span().into(),
+        }
+    }
+}
+
+impl ToTokens for NestedMeta {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            NestedMeta::Meta(meta) => meta.to_tokens(tokens),
+            NestedMeta::Lit(lit) => lit.to_tokens(tokens),
+        }
+    }
+}
+
+/// Accepts the contents of an attribute: a path, optionally followed by a delimited list or an
+/// `=` and a value
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::attr::{meta, Meta};
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = meta::<Cheap<_, RustSpan>>().then_ignore(end());
+///
+/// let meta = parser.parse(stream_from_tokens(quote!(rename = "x"))).unwrap();
+/// assert!(matches!(meta, Meta::NameValue(_)));
+///
+/// let meta = parser.parse(stream_from_tokens(quote!(serde(skip, rename = "y")))).unwrap();
+/// match meta {
+///     Meta::List(list) => assert_eq!(list.parse_nested::<Cheap<_, _>>().unwrap().len(), 2),
+///     _ => panic!("Expected a list"),
+/// }
+///
+/// parser.parse(stream_from_tokens(quote!(rename "x"))).unwrap_err();
+/// ```
+#[must_use]
+pub fn meta<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Meta, Error = E> + Clone {
+    let list = filter_map(|span, tok: RustToken| {
+        tok.into_start_delim()
+            .map_err(|tok| E::expected_input_found(span, [], Some(tok)))
+    })
+    .map_with_span(|delimiter, span| (delimiter, span))
+    .then(token_tree().repeated().flatten())
+    .then(
+        filter_map(|span, tok: RustToken| {
+            tok.into_end_delim()
+                .map_err(|tok| E::expected_input_found(span, [], Some(tok)))
+        })
+        .map_with_span(|_, span| span),
+    )
+    .map(|(((delimiter, open_token), tokens), close_token)| {
+        (delimiter, open_token, close_token, Verbatim::new(tokens))
+    });
+
+    let name_value = punct('=').map_with_span(|(), span| span).then(expr());
+
+    path()
+        .then(list.map(Ok).or(name_value.map(Err)).or_not())
+        .map(|(path, rest)| match rest {
+            None => Meta::Path(path),
+            Some(Ok((delimiter, open_token, close_token, tokens))) => Meta::List(MetaList {
+                path,
+                delimiter,
+                open_token,
+                close_token,
+                tokens,
+            }),
+            Some(Err((eq_token, value))) => Meta::NameValue(MetaNameValue {
+                path,
+                eq_token,
+                value,
+            }),
+        })
+        .boxed()
+}
+
+/// Accepts an item in a nested attribute list - either a meta or a literal
+#[must_use]
+pub fn nested_meta<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, NestedMeta, Error = E> + Clone {
+    filter_map(RustToken::filter_literal)
+        .map(NestedMeta::Lit)
+        .or(meta().map(NestedMeta::Meta))
+}
+
+/// Accepts an attribute of the given style
+fn attr<E: Error<RustToken, Span = RustSpan> + 'static>(
+    style: AttrStyle,
+) -> impl Parser<RustToken, Attribute, Error = E> + Clone {
+    let pound = punct('#').map_with_span(|(), span: RustSpan| span);
+    let bang = punct('!').map_with_span(|(), span: RustSpan| span);
+    let pound = match style {
+        AttrStyle::Outer => pound.map(|pound| (pound, None)).boxed(),
+        AttrStyle::Inner => pound.then(bang.map(Some)).boxed(),
+    };
+
+    pound
+        .then(just(RustToken::StartDelim(Delimiter::Bracket)).map_with_span(|_, span| span))
+        .then(meta())
+        .then(just(RustToken::EndDelim(Delimiter::Bracket)).map_with_span(|_, span| span))
+        .map(
+            move |((((pound_token, bang_token), bracket_token), meta), close_token)| Attribute {
+                style,
+                pound_token,
+                bang_token,
+                bracket_token,
+                close_token,
+                meta,
+            },
+        )
+}
+
+/// Accepts an outer attribute, such as `#[derive(Debug)]`
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::attr::{filter_attrs, outer_attrs};
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = outer_attrs::<Cheap<_, RustSpan>>().then_ignore(keyword("struct"));
+///
+/// let attrs = parser
+///     .parse(stream_from_tokens(quote!(
+///         #[derive(Debug)]
+///         #[my_derive(rename = "x")]
+///         #[my_derive(skip)]
+///         struct
+///     )))
+///     .unwrap();
+/// assert_eq!(attrs.len(), 3);
+/// assert_eq!(filter_attrs(&attrs, "my_derive").count(), 2);
+///
+/// parser.parse(stream_from_tokens(quote!(#![no_std] struct))).unwrap_err();
+/// ```
+#[must_use]
+pub fn outer_attr<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Attribute, Error = E> + Clone {
+    attr(AttrStyle::Outer)
+}
+
+/// Accepts an inner attribute, such as `#![no_std]`
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::attr::{inner_attr, AttrStyle};
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = inner_attr::<Cheap<_, RustSpan>>();
+///
+/// let attr = parser.parse(stream_from_tokens(quote!(#![no_std]))).unwrap();
+/// assert_eq!(attr.style, AttrStyle::Inner);
+/// assert!(attr.path_is("no_std"));
+///
+/// parser.parse(stream_from_tokens(quote!(#[no_std]))).unwrap_err();
+/// ```
+#[must_use]
+pub fn inner_attr<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Attribute, Error = E> + Clone {
+    attr(AttrStyle::Inner)
+}
+
+/// Accepts any number of outer attributes
+#[must_use]
+pub fn outer_attrs<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Vec<Attribute>, Error = E> + Clone {
+    outer_attr().repeated()
+}
+
+/// Accepts any number of inner attributes
+#[must_use]
+pub fn inner_attrs<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Vec<Attribute>, Error = E> + Clone {
+    inner_attr().repeated()
+}
@@ -0,0 +1,261 @@
+//! Doc comments, which arrive as `#[doc = "..."]` or `#![doc = "..."]` attributes
+//!
+//! By default, doc comments are ordinary attribute tokens. Streams created with
+//! [`stream_with_docs`] instead collapse each one into a single [`RustToken::DocComment`], so
+//! grammars can treat them as one token. The parsers here accept either form.
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use chumsky::Stream;
+use proc_macro2::{Delimiter, Ident, Literal, Punct, Spacing, TokenStream};
+use quote::ToTokens;
+
+use super::attr::{AttrStyle, Attribute, Meta};
+use super::Tokens;
+use crate::primitive::{keyword, punct};
+use crate::utils::{from_slice, into_vec, unescape_str};
+use crate::{RustSpan, RustToken};
+
+/// A single doc comment, such as `/// Some docs` or `//! Crate docs`
+#[derive(Clone, Debug)]
+pub struct DocComment {
+    /// Whether this documents the following item, or the enclosing one
+    pub style: AttrStyle,
+    /// The string literal holding the comment, as written
+    pub lit: Literal,
+    /// The unescaped text of the comment. For a `///` comment, this includes the space after the
+    /// slashes.
+    pub text: String,
+}
+
+impl DocComment {
+    /// Get the span of this comment's text
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        self.lit.span().into()
+    }
+
+    /// Get the flat tokens of the `#[doc = "..."]` attribute this comment is made of
+    pub(crate) fn tokens(&self) -> Tokens {
+        let span = self.lit.span();
+        let spanned = |tok| (tok, RustSpan::from(span));
+        let mut pound = Punct::new('#', Spacing::Alone);
+        pound.set_span(span);
+        let mut eq = Punct::new('=', Spacing::Alone);
+        eq.set_span(span);
+
+        let mut tokens = vec![spanned(RustToken::Punct(pound))];
+        if self.style == AttrStyle::Inner {
+            let mut bang = Punct::new('!', Spacing::Alone);
+            bang.set_span(span);
+            tokens.push(spanned(RustToken::Punct(bang)));
+        }
+        tokens.extend([
+            spanned(RustToken::StartDelim(Delimiter::Bracket)),
+            spanned(RustToken::Ident(Ident::new("doc", span))),
+            spanned(RustToken::Punct(eq)),
+            spanned(RustToken::Literal(self.lit.clone())),
+            spanned(RustToken::EndDelim(Delimiter::Bracket)),
+        ]);
+        tokens
+    }
+}
+
+impl ToTokens for DocComment {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(from_slice(&self.tokens()));
+    }
+}
+
+/// All the doc comments on an item, in order
+#[derive(Clone, Debug, Default)]
+pub struct Docs {
+    /// The individual comments, one per line for `///` style docs
+    pub lines: Vec<DocComment>,
+}
+
+impl Docs {
+    /// Collect the doc comments from a list of attributes, ignoring any other attributes
+    #[must_use]
+    pub fn from_attrs(attrs: &[Attribute]) -> Docs {
+        let lines = attrs
+            .iter()
+            .filter_map(|attr| match &attr.meta {
+                Meta::NameValue(name_value) if name_value.path.is_ident("doc") => {
+                    let lit = name_value.lit()?;
+                    Some(DocComment {
+                        style: attr.style,
+                        text: unescape_str(lit)?,
+                        lit: lit.clone(),
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+        Docs { lines }
+    }
+
+    /// Returns whether there are no doc comments
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Get the text of every comment, joined by newlines
+    #[must_use]
+    pub fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| &*line.text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Iterate over each line of text alongside the span of the comment it came from. Block
+    /// comments spanning several lines give the same span for each line.
+    pub fn spanned_lines(&self) -> impl Iterator<Item = (&str, RustSpan)> {
+        self.lines
+            .iter()
+            .flat_map(|doc| doc.text.lines().map(move |line| (line, doc.span())))
+    }
+}
+
+impl ToTokens for Docs {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for line in &self.lines {
+            line.to_tokens(tokens);
+        }
+    }
+}
+
+/// Accepts a doc comment, either as a `#[doc = "..."]` or `#![doc = "..."]` attribute, or as a
+/// single [`RustToken::DocComment`]
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::doc::{doc_comment, docs, stream_with_docs};
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = doc_comment::<Cheap<_, RustSpan>>();
+///
+/// let doc = parser.parse(stream_from_tokens(quote!(#[doc = " Some \"docs\""]))).unwrap();
+/// assert_eq!(doc.text, " Some \"docs\"");
+///
+/// let docs = docs::<Cheap<_, RustSpan>>()
+///     .then_ignore(keyword("struct"))
+///     .parse(stream_with_docs(quote!(
+///         /// First line
+///         /// Second line
+///         struct
+///     )))
+///     .unwrap();
+/// assert_eq!(docs.text(), " First line\n Second line");
+///
+/// parser.parse(stream_from_tokens(quote!(#[derive(Debug)]))).unwrap_err();
+/// ```
+#[must_use]
+pub fn doc_comment<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, DocComment, Error = E> + Clone {
+    let attr = punct('#')
+        .ignore_then(punct('!').or_not())
+        .then_ignore(just(RustToken::StartDelim(Delimiter::Bracket)))
+        .then_ignore(keyword("doc"))
+        .then_ignore(punct('='))
+        .then(filter_map(RustToken::filter_literal))
+        .then_ignore(just(RustToken::EndDelim(Delimiter::Bracket)))
+        .try_map(|(bang, lit), span| {
+            let text = unescape_str(&lit).ok_or_else(|| {
+                E::expected_input_found(span, [], Some(RustToken::Literal(lit.clone())))
+            })?;
+            Ok(DocComment {
+                style: if bang.is_some() {
+                    AttrStyle::Inner
+                } else {
+                    AttrStyle::Outer
+                },
+                lit,
+                text,
+            })
+        });
+
+    filter_map(|span, tok: RustToken| {
+        tok.into_doc_comment()
+            .map_err(|tok| E::expected_input_found(span, [], Some(tok)))
+    })
+    .or(attr)
+}
+
+/// Accepts any number of doc comments, collecting them together
+#[must_use]
+pub fn docs<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Docs, Error = E> + Clone {
+    doc_comment().repeated().map(|lines| Docs { lines })
+}
+
+/// Generate a chumsky `Stream` from a Rust `TokenStream`, like [`stream_from_tokens`], but with
+/// each `#[doc = "..."]` attribute collapsed into a single [`RustToken::DocComment`]
+///
+/// [`stream_from_tokens`]: crate::stream_from_tokens
+#[must_use]
+pub fn stream_with_docs(
+    stream: TokenStream,
+) -> Stream<'static, RustToken, RustSpan, impl Iterator<Item = (RustToken, RustSpan)>> {
+    let tokens = collapse_docs(into_vec(stream));
+
+    Stream::from_iter(proc_macro2::Span::mixed_site().into(), tokens.into_iter())
+}
+
+/// Collapse every `#[doc = "..."]` attribute in a run of flat tokens into a single token
+fn collapse_docs(tokens: Tokens) -> Tokens {
+    let mut out = Vec::with_capacity(tokens.len());
+    let mut idx = 0;
+
+    while idx < tokens.len() {
+        match doc_at(&tokens[idx..]) {
+            Some((doc, len)) => {
+                out.push(doc);
+                idx += len;
+            }
+            None => {
+                out.push(tokens[idx].clone());
+                idx += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/// Read a `#[doc = "..."]` attribute from the start of a run of flat tokens, returning it as a
+/// single token alongside the number of tokens it replaces
+fn doc_at(tokens: &[(RustToken, RustSpan)]) -> Option<((RustToken, RustSpan), usize)> {
+    let (style, rest) = match tokens {
+        [(RustToken::Punct(pound), _), (RustToken::Punct(bang), _), rest @ ..]
+            if pound.as_char() == '#' && bang.as_char() == '!' =>
+        {
+            (AttrStyle::Inner, rest)
+        }
+        [(RustToken::Punct(pound), _), rest @ ..] if pound.as_char() == '#' => {
+            (AttrStyle::Outer, rest)
+        }
+        _ => return None,
+    };
+
+    match rest {
+        [(RustToken::StartDelim(Delimiter::Bracket), _), (RustToken::Ident(ident), _), (RustToken::Punct(eq), _), (RustToken::Literal(lit), _), (RustToken::EndDelim(Delimiter::Bracket), _), ..]
+            if ident == "doc" && eq.as_char() == '=' =>
+        {
+            let doc = DocComment {
+                style,
+                lit: lit.clone(),
+                text: unescape_str(lit)?,
+            };
+            let len = tokens.len() - rest.len() + 5;
+            Some(((RustToken::DocComment(doc), lit.span().into()), len))
+        }
+        _ => None,
+    }
+}
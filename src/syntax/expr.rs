@@ -0,0 +1,312 @@
+//! Expressions, such as `a + b * c` or `if x { y } else { z }`
+//!
+//! Expressions are recognized rather than fully parsed - the parsers here find where an
+//! expression ends, following Rust's rules, and keep its tokens.
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use proc_macro2::{Delimiter, Punct, Spacing};
+
+use super::pat::pat_tokens;
+use super::ty::{segment_ident_token, TypeGrammar};
+use super::{
+    any_group_tokens, arrow_tokens, group_tokens, joint_token, keyword_token, lifetime_tokens,
+    literal_token, path_sep_tokens, punct_token, token, verbatim_node, Tokens, Verbatim,
+};
+use crate::{RustSpan, RustToken};
+
+verbatim_node! {
+    /// An expression, such as `a + b * c` or `if x { y } else { z }`. Expressions are validated
+    /// while parsing, but kept as raw tokens.
+    Expr
+}
+
+/// Accepts an expression. The expression ends at the first token which can't continue it, such
+/// as a `,`, `;` or `=>` outside of any group.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::expr::expr;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = expr::<Cheap<_, RustSpan>>().then_ignore(punct(','));
+///
+/// parser.parse(stream_from_tokens(quote!(a + b.c(d)?[0] as u8,))).unwrap();
+/// parser.parse(stream_from_tokens(quote!(if x { y } else { z },))).unwrap();
+/// parser.parse(stream_from_tokens(quote!(move |a: u8| a + 1,))).unwrap();
+/// parser.parse(stream_from_tokens(quote!(Foo { a: 1 }.b..=c,))).unwrap();
+///
+/// parser.parse(stream_from_tokens(quote!(a +,))).unwrap_err();
+/// ```
+#[must_use]
+pub fn expr<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Expr, Error = E> + Clone {
+    ExprGrammar::new()
+        .expr
+        .map(|tokens| Expr(Verbatim::new(tokens)))
+}
+
+/// The characters which can make up a binary operator
+const OPERATOR_CHARS: &[char] = &[
+    '+', '-', '*', '/', '%', '^', '!', '&', '|', '<', '>', '=', '.',
+];
+
+/// The mutually recursive parsers making up the expression grammar
+pub(crate) struct ExprGrammar<E: Error<RustToken, Span = RustSpan> + 'static> {
+    /// Accepts an expression, keeping its tokens
+    pub expr: BoxedParser<'static, RustToken, Tokens, E>,
+    /// Accepts a block-like expression, such as `if x {}` or `loop {}`, which ends a statement
+    /// without a `;`, keeping its tokens
+    pub block_like: BoxedParser<'static, RustToken, Tokens, E>,
+    /// Accepts a macro invocation with braces, such as `foo! {}`, which ends a statement without
+    /// a `;`, keeping its tokens
+    pub brace_mac: BoxedParser<'static, RustToken, Tokens, E>,
+}
+
+impl<E: Error<RustToken, Span = RustSpan> + 'static> ExprGrammar<E> {
+    /// Build the expression grammar
+    pub fn new() -> ExprGrammar<E> {
+        let types = TypeGrammar::new();
+
+        let mut expr = Recursive::declare();
+        let mut expr_no_struct = Recursive::declare();
+        let mut if_expr = Recursive::declare();
+
+        let block = group_tokens(Delimiter::Brace).boxed();
+        let label = lifetime_tokens().chain(punct_token(':')).or_not().flatten();
+
+        if_expr.define(
+            keyword_token("if")
+                .chain(expr_no_struct.clone())
+                .chain(block.clone())
+                .chain(
+                    keyword_token("else")
+                        .chain(if_expr.clone().or(block.clone()))
+                        .or_not()
+                        .flatten(),
+                ),
+        );
+
+        let block_like = label
+            .chain(choice((
+                block.clone(),
+                keyword_token("unsafe").chain(block.clone()),
+                keyword_token("const").chain(block.clone()),
+                keyword_token("async")
+                    .chain(keyword_token("move").or_not())
+                    .chain(block.clone()),
+                keyword_token("loop").chain(block.clone()),
+                keyword_token("while")
+                    .chain(expr_no_struct.clone())
+                    .chain(block.clone()),
+                keyword_token("for")
+                    .chain(pat_tokens(true))
+                    .chain(keyword_token("in"))
+                    .chain(expr_no_struct.clone())
+                    .chain(block.clone()),
+                keyword_token("match")
+                    .chain(expr_no_struct.clone())
+                    .chain(block.clone()),
+                if_expr.clone(),
+            )))
+            .boxed();
+
+        let params = punct_token('|')
+            .chain(
+                pat_tokens(false)
+                    .chain(punct_token(':').chain(types.ty.clone()).or_not().flatten())
+                    .chain(punct_token(',').or_not())
+                    .repeated()
+                    .flatten(),
+            )
+            .chain(punct_token('|'));
+
+        let closure = keyword_token("async")
+            .or_not()
+            .chain(keyword_token("move").or_not())
+            .chain(params)
+            .chain(
+                arrow_tokens()
+                    .chain(types.ty.clone())
+                    .chain(block.clone())
+                    .or(expr.clone()),
+            )
+            .boxed();
+
+        let turbofish = path_sep_tokens().chain(types.generic_args.clone());
+        let segment = segment_ident_token().chain(turbofish.clone().or_not().flatten());
+        let path = path_sep_tokens()
+            .or_not()
+            .flatten()
+            .chain(segment.clone())
+            .chain(
+                path_sep_tokens()
+                    .chain(segment.clone())
+                    .repeated()
+                    .flatten(),
+            );
+        let qualified = punct_token('<')
+            .chain(types.ty.clone())
+            .chain(
+                keyword_token("as")
+                    .chain(types.path.clone())
+                    .or_not()
+                    .flatten(),
+            )
+            .chain(punct_token('>'))
+            .chain(
+                path_sep_tokens()
+                    .chain(segment)
+                    .repeated()
+                    .at_least(1)
+                    .flatten(),
+            );
+        let path = path.or(qualified).boxed();
+
+        let mac = path
+            .clone()
+            .chain(punct_token('!'))
+            .chain(any_group_tokens());
+        let brace_mac = path
+            .clone()
+            .chain(punct_token('!'))
+            .chain(group_tokens(Delimiter::Brace))
+            .boxed();
+
+        let jump = choice((
+            keyword_token("return").chain(expr.clone().or_not().flatten()),
+            keyword_token("yield").chain(expr.clone().or_not().flatten()),
+            keyword_token("become").chain(expr.clone()),
+            keyword_token("break")
+                .chain(lifetime_tokens().or_not().flatten())
+                .chain(expr.clone().or_not().flatten()),
+            keyword_token("continue").chain(lifetime_tokens().or_not().flatten()),
+        ))
+        .boxed();
+
+        let member = punct_token('.')
+            .chain(token(None, |tok| tok.is_ident() || tok.is_literal()))
+            .chain(turbofish.or_not().flatten());
+
+        let postfix = choice((
+            punct_token('?').map(|tok| vec![tok]),
+            member.boxed(),
+            group_tokens(Delimiter::Parenthesis),
+            group_tokens(Delimiter::Bracket),
+            keyword_token("as").chain(types.ty.clone()).boxed(),
+        ))
+        .repeated()
+        .flatten()
+        .boxed();
+
+        let prefix = choice((
+            keyword_token("let")
+                .chain(pat_tokens(true))
+                .chain(punct_token('=')),
+            punct_token('&')
+                .chain(keyword_token("mut").or_not())
+                .boxed(),
+            token(None, |tok| {
+                tok.as_punct()
+                    .is_some_and(|p| matches!(p.as_char(), '-' | '!' | '*'))
+            })
+            .map(|tok| vec![tok])
+            .boxed(),
+        ))
+        .repeated()
+        .flatten()
+        .boxed();
+
+        let binary_op = joint_token_in(OPERATOR_CHARS)
+            .repeated()
+            .chain(token(None, |tok| {
+                tok.as_punct()
+                    .is_some_and(|p| OPERATOR_CHARS.contains(&p.as_char()))
+            }))
+            .try_map(|ops: Tokens, span| {
+                // Runs of joint punctuation are treated as one operator. `=>` ends an expression,
+                // and `.` or `!` alone aren't binary operators.
+                let chars = ops
+                    .iter()
+                    .filter_map(|(tok, _)| tok.as_punct().map(Punct::as_char))
+                    .collect::<String>();
+                if chars.starts_with("=>") || chars == "." || chars == "!" {
+                    Err(E::expected_input_found(span, [], None))
+                } else {
+                    Ok(ops)
+                }
+            })
+            .boxed();
+
+        let dots = joint_token('.')
+            .chain(punct_token('.'))
+            .chain(
+                token(None, |tok| {
+                    tok.as_punct().is_some_and(|p| p.as_char() == '=')
+                })
+                .or_not(),
+            )
+            .boxed();
+
+        let operands = |allow_struct: bool, expr: Recursive<'static, RustToken, Tokens, E>| {
+            let path_expr = if allow_struct {
+                path.clone()
+                    .chain(group_tokens(Delimiter::Brace).or_not().flatten())
+                    .boxed()
+            } else {
+                path.clone()
+            };
+
+            let operand = choice((
+                literal_token().map(|tok| vec![tok]),
+                keyword_token("true")
+                    .or(keyword_token("false"))
+                    .or(keyword_token("_"))
+                    .map(|tok| vec![tok]),
+                group_tokens(Delimiter::Parenthesis),
+                group_tokens(Delimiter::Bracket),
+                block_like.clone(),
+                closure.clone(),
+                jump.clone(),
+                mac.clone().boxed(),
+                path_expr,
+            ));
+
+            let unary = prefix.clone().chain(operand).chain(postfix.clone()).boxed();
+
+            let binary_tail = binary_op
+                .clone()
+                .chain(unary.clone())
+                .repeated()
+                .flatten()
+                .chain(dots.clone().or_not().flatten());
+
+            dots.clone()
+                .chain(expr.or_not().flatten())
+                .or(unary.chain(binary_tail))
+        };
+
+        expr.define(operands(true, expr.clone()));
+        expr_no_struct.define(operands(false, expr_no_struct.clone()));
+
+        ExprGrammar {
+            expr: expr.boxed(),
+            block_like,
+            brace_mac,
+        }
+    }
+}
+
+/// Accepts a single punctuation token from the given set which is joined to the next token,
+/// keeping the token
+fn joint_token_in<E: Error<RustToken, Span = RustSpan>>(
+    chars: &'static [char],
+) -> impl Parser<RustToken, (RustToken, RustSpan), Error = E> + Clone {
+    token(None, move |tok| {
+        tok.as_punct()
+            .is_some_and(|p| chars.contains(&p.as_char()) && p.spacing() == Spacing::Joint)
+    })
+}
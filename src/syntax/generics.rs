@@ -0,0 +1,511 @@
+//! Generic parameter lists and where clauses, such as `<'a, T: Clone = u8, const N: usize>`
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, ToTokens};
+
+use super::ty::{const_arg_tokens, for_lifetimes, ty, TypeGrammar};
+use super::{
+    lifetime, punct_span, punctuated, sym, Lifetime, Punctuated, Type, TypeBound, Verbatim,
+};
+use crate::primitive::{ident, keyword, punct};
+use crate::{RustSpan, RustToken};
+
+/// A list of generic parameters, as found after the name of a type, trait or function
+#[derive(Clone, Debug, Default)]
+pub struct Generics {
+    /// The span of the opening `<`, if the list was present
+    pub lt_token: Option<RustSpan>,
+    /// The generic parameters
+    pub params: Punctuated<GenericParam, ','>,
+    /// The span of the closing `>`, if the list was present
+    pub gt_token: Option<RustSpan>,
+    /// The where clause associated with these generics. This isn't filled in by [`generics`], as
+    /// the clause is usually separated from the parameters.
+    pub where_clause: Option<WhereClause>,
+}
+
+impl Generics {
+    /// Split these generics into the pieces needed to write an impl block for the type they're
+    /// declared on - `impl #impl_generics Trait for Name #ty_generics #where_clause`
+    #[must_use]
+    pub fn split_for_impl(&self) -> (ImplGenerics<'_>, TypeGenerics<'_>, Option<&WhereClause>) {
+        (
+            ImplGenerics(self),
+            TypeGenerics(self),
+            self.where_clause.as_ref(),
+        )
+    }
+
+    /// Iterate over all type parameters
+    pub fn type_params(&self) -> impl Iterator<Item = &TypeParam> {
+        self.params.iter().filter_map(|param| match param {
+            GenericParam::Type(param) => Some(param),
+            _ => None,
+        })
+    }
+
+    /// Iterate mutably over all type parameters
+    pub fn type_params_mut(&mut self) -> impl Iterator<Item = &mut TypeParam> {
+        self.params.iter_mut().filter_map(|param| match param {
+            GenericParam::Type(param) => Some(param),
+            _ => None,
+        })
+    }
+
+    /// Iterate over all lifetime parameters
+    pub fn lifetimes(&self) -> impl Iterator<Item = &LifetimeParam> {
+        self.params.iter().filter_map(|param| match param {
+            GenericParam::Lifetime(param) => Some(param),
+            _ => None,
+        })
+    }
+
+    /// Add a trait bound, such as `MyTrait` or `Into<String>`, to every type parameter
+    pub fn add_type_bound<T: ToTokens>(&mut self, bound: T) {
+        let bound = Verbatim::from(bound.into_token_stream());
+        for param in self.type_params_mut() {
+            param.bounds.push(TypeBound::Trait(bound.clone()));
+        }
+    }
+
+    /// Get the where clause for these generics, creating an empty one if there isn't one already
+    pub fn make_where_clause(&mut self) -> &mut WhereClause {
+        self.where_clause.get_or_insert_with(|| WhereClause {
+            where_token: Span::call_site().into(),
+            predicates: Punctuated::new(),
+        })
+    }
+}
+
+impl ToTokens for Generics {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if self.params.is_empty() {
+            return;
+        }
+        let call_site = || Span::call_site().into();
+        sym('<', self.lt_token.unwrap_or_else(call_site)).to_tokens(tokens);
+        self.params.to_tokens(tokens);
+        sym('>', self.gt_token.unwrap_or_else(call_site)).to_tokens(tokens);
+    }
+}
+
+/// The generics of a type, formatted for use after `impl`. Parameters keep their bounds but lose
+/// their defaults. Created by [`Generics::split_for_impl`].
+#[derive(Copy, Clone, Debug)]
+pub struct ImplGenerics<'a>(&'a Generics);
+
+impl ToTokens for ImplGenerics<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if self.0.params.is_empty() {
+            return;
+        }
+        let params = self.0.params.iter().map(|param| match param {
+            GenericParam::Lifetime(param) => param.to_token_stream(),
+            GenericParam::Type(TypeParam { ident, bounds, .. }) => {
+                if bounds.is_empty() {
+                    ident.to_token_stream()
+                } else {
+                    quote!(#ident: #bounds)
+                }
+            }
+            GenericParam::Const(ConstParam { ident, ty, .. }) => quote!(const #ident: #ty),
+        });
+        tokens.extend(quote!(<#(#params),*>));
+    }
+}
+
+/// The generics of a type, formatted for use after the type's name. Only the names of the
+/// parameters are kept. Created by [`Generics::split_for_impl`].
+#[derive(Copy, Clone, Debug)]
+pub struct TypeGenerics<'a>(&'a Generics);
+
+impl ToTokens for TypeGenerics<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if self.0.params.is_empty() {
+            return;
+        }
+        let params = self.0.params.iter().map(|param| match param {
+            GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+            GenericParam::Type(TypeParam { ident, .. })
+            | GenericParam::Const(ConstParam { ident, .. }) => ident.to_token_stream(),
+        });
+        tokens.extend(quote!(<#(#params),*>));
+    }
+}
+
+/// A single generic parameter
+#[derive(Clone, Debug)]
+pub enum GenericParam {
+    /// A lifetime parameter, such as `'a: 'b`
+    Lifetime(LifetimeParam),
+    /// A type parameter, such as `T: Clone = u8`
+    Type(TypeParam),
+    /// A const parameter, such as `const N: usize = 3`
+    Const(ConstParam),
+}
+
+impl ToTokens for GenericParam {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            GenericParam::Lifetime(param) => param.to_tokens(tokens),
+            GenericParam::Type(param) => param.to_tokens(tokens),
+            GenericParam::Const(param) => param.to_tokens(tokens),
+        }
+    }
+}
+
+/// A lifetime parameter, such as `'a: 'b + 'c`
+#[derive(Clone, Debug)]
+pub struct LifetimeParam {
+    /// The declared lifetime
+    pub lifetime: Lifetime,
+    /// The span of the `:` before the bounds, if present
+    pub colon_token: Option<RustSpan>,
+    /// The lifetimes this one must outlive
+    pub bounds: Punctuated<Lifetime, '+'>,
+}
+
+impl ToTokens for LifetimeParam {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let LifetimeParam {
+            lifetime,
+            colon_token,
+            bounds,
+        } = self;
+        lifetime.to_tokens(tokens);
+        bounds_to_tokens(*colon_token, bounds, tokens);
+    }
+}
+
+/// A type parameter, such as `T: Clone = u8`
+#[derive(Clone, Debug)]
+pub struct TypeParam {
+    /// The name of the parameter
+    pub ident: Ident,
+    /// The span of the `:` before the bounds, if present
+    pub colon_token: Option<RustSpan>,
+    /// The bounds on the parameter
+    pub bounds: Punctuated<TypeBound, '+'>,
+    /// The span of the `=` and the default type, if one was given
+    pub default: Option<(RustSpan, Type)>,
+}
+
+impl ToTokens for TypeParam {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let TypeParam {
+            ident,
+            colon_token,
+            bounds,
+            default,
+        } = self;
+        ident.to_tokens(tokens);
+        bounds_to_tokens(*colon_token, bounds, tokens);
+        if let Some((eq_token, default)) = default {
+            sym('=', *eq_token).to_tokens(tokens);
+            default.to_tokens(tokens);
+        }
+    }
+}
+
+/// A const parameter, such as `const N: usize = 3`
+#[derive(Clone, Debug)]
+pub struct ConstParam {
+    /// The span of the `const` keyword
+    pub const_token: RustSpan,
+    /// The name of the parameter
+    pub ident: Ident,
+    /// The span of the `:` before the type
+    pub colon_token: RustSpan,
+    /// The type of the parameter
+    pub ty: Type,
+    /// The span of the `=` and the default value, if one was given
+    pub default: Option<(RustSpan, Verbatim)>,
+}
+
+impl ToTokens for ConstParam {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ConstParam {
+            const_token,
+            ident,
+            colon_token,
+            ty,
+            default,
+        } = self;
+        tokens.extend([Ident::new("const", **const_token)]);
+        ident.to_tokens(tokens);
+        sym(':', *colon_token).to_tokens(tokens);
+        ty.to_tokens(tokens);
+        if let Some((eq_token, default)) = default {
+            sym('=', *eq_token).to_tokens(tokens);
+            default.to_tokens(tokens);
+        }
+    }
+}
+
+/// A where clause, such as `where T: Clone, 'a: 'b`
+#[derive(Clone, Debug)]
+pub struct WhereClause {
+    /// The span of the `where` keyword
+    pub where_token: RustSpan,
+    /// The predicates in the clause
+    pub predicates: Punctuated<WherePredicate, ','>,
+}
+
+impl ToTokens for WhereClause {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if self.predicates.is_empty() {
+            return;
+        }
+        tokens.extend([Ident::new("where", *self.where_token)]);
+        self.predicates.to_tokens(tokens);
+    }
+}
+
+/// A single predicate in a where clause
+#[derive(Clone, Debug)]
+pub enum WherePredicate {
+    /// A lifetime predicate, such as `'a: 'b`
+    Lifetime {
+        /// The bounded lifetime
+        lifetime: Lifetime,
+        /// The span of the `:` before the bounds
+        colon_token: RustSpan,
+        /// The lifetimes it must outlive
+        bounds: Punctuated<Lifetime, '+'>,
+    },
+    /// A type predicate, such as `for<'a> T: Trait<'a>`
+    Type {
+        /// A higher-ranked lifetime binder, such as `for<'a>`, if present
+        lifetimes: Option<Verbatim>,
+        /// The bounded type
+        bounded_ty: Type,
+        /// The span of the `:` before the bounds
+        colon_token: RustSpan,
+        /// The bounds on the type
+        bounds: Punctuated<TypeBound, '+'>,
+    },
+}
+
+impl ToTokens for WherePredicate {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            WherePredicate::Lifetime {
+                lifetime,
+                colon_token,
+                bounds,
+            } => {
+                lifetime.to_tokens(tokens);
+                sym(':', *colon_token).to_tokens(tokens);
+                bounds.to_tokens(tokens);
+            }
+            WherePredicate::Type {
+                lifetimes,
+                bounded_ty,
+                colon_token,
+                bounds,
+            } => {
+                tokens.extend(quote!(#lifetimes #bounded_ty));
+                sym(':', *colon_token).to_tokens(tokens);
+                bounds.to_tokens(tokens);
+            }
+        }
+    }
+}
+
+/// Write the `:` and bounds of a generic parameter. Bounds added after parsing get a `:` at the
+/// call site.
+fn bounds_to_tokens<T: ToTokens>(
+    colon_token: Option<RustSpan>,
+    bounds: &Punctuated<T, '+'>,
+    tokens: &mut TokenStream,
+) {
+    if colon_token.is_none() && bounds.is_empty() {
+        return;
+    }
+    sym(':', colon_token.unwrap_or_else(|| Span::call_site().into())).to_tokens(tokens);
+    bounds.to_tokens(tokens);
+}
+
+/// Accepts the bounds of a lifetime, such as `'b + 'c`
+fn lifetime_bounds<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Punctuated<Lifetime, '+'>, Error = E> + Clone {
+    punctuated(lifetime())
+        .or_not()
+        .map(Option::unwrap_or_default)
+}
+
+/// Accepts an optional list of generic parameters. When no list is present, the output is empty.
+/// Where clauses are parsed separately, with [`where_clause`].
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::generics::{generics, where_clause};
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = keyword::<Cheap<_, RustSpan>>("struct")
+///     .ignore_then(ident())
+///     .then(generics())
+///     .then(where_clause().or_not())
+///     .map(|((name, mut generics), where_clause)| {
+///         generics.where_clause = where_clause;
+///         (name, generics)
+///     });
+///
+/// let (name, mut generics) = parser
+///     .parse(stream_from_tokens(quote!(
+///         struct Foo<'a, T: ?Sized = str, const N: usize = 3> where T: 'a
+///     )))
+///     .unwrap();
+///
+/// generics.add_type_bound(quote!(MyTrait));
+/// let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+/// assert_eq!(
+///     quote!(impl #impl_generics MyTrait for #name #ty_generics #where_clause {}).to_string(),
+///     quote!(
+///         impl<'a, T: ?Sized + MyTrait, const N: usize> MyTrait for Foo<'a, T, N> where T: 'a {}
+///     )
+///     .to_string(),
+/// );
+/// ```
+#[must_use]
+pub fn generics<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Generics, Error = E> + Clone {
+    let lifetime_param = lifetime()
+        .then(punct_span(':').then(lifetime_bounds()).or_not())
+        .map(|(lifetime, bounds)| {
+            let (colon_token, bounds) = match bounds {
+                Some((colon_token, bounds)) => (Some(colon_token), bounds),
+                None => (None, Punctuated::new()),
+            };
+            GenericParam::Lifetime(LifetimeParam {
+                lifetime,
+                colon_token,
+                bounds,
+            })
+        });
+
+    let grammar = TypeGrammar::new();
+
+    let type_param = ident()
+        .then(punct_span(':').then(grammar.bounds.or_not()).or_not())
+        .then(punct_span('=').then(ty()).or_not())
+        .map(|((ident, bounds), default)| {
+            let (colon_token, bounds) = match bounds {
+                Some((colon_token, bounds)) => (Some(colon_token), bounds.unwrap_or_default()),
+                None => (None, Punctuated::new()),
+            };
+            GenericParam::Type(TypeParam {
+                ident,
+                colon_token,
+                bounds,
+                default,
+            })
+        });
+
+    let const_param = keyword("const")
+        .map_with_span(|(), span| span)
+        .then(ident())
+        .then(punct_span(':'))
+        .then(ty())
+        .then(
+            punct_span('=')
+                .then(const_arg_tokens().or(grammar.path).map(Verbatim::new))
+                .or_not(),
+        )
+        .map(|((((const_token, ident), colon_token), ty), default)| {
+            GenericParam::Const(ConstParam {
+                const_token,
+                ident,
+                colon_token,
+                ty,
+                default,
+            })
+        });
+
+    punct('<')
+        .map_with_span(|(), span| span)
+        .then(
+            punctuated(choice((lifetime_param, const_param, type_param)))
+                .or_not()
+                .map(Option::unwrap_or_default),
+        )
+        .then(punct('>').map_with_span(|(), span| span))
+        .or_not()
+        .map(|list| match list {
+            Some(((lt_token, params), gt_token)) => Generics {
+                lt_token: Some(lt_token),
+                params,
+                gt_token: Some(gt_token),
+                where_clause: None,
+            },
+            None => Generics::default(),
+        })
+        .boxed()
+}
+
+/// Accepts a where clause, such as `where T: Clone, 'a: 'b`
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::generics::where_clause;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = where_clause::<Cheap<_, RustSpan>>();
+///
+/// let clause = parser
+///     .parse(stream_from_tokens(quote!(where T: Clone, for<'a> &'a T: Into<u8>, 'a: 'b)))
+///     .unwrap();
+/// assert_eq!(clause.predicates.len(), 3);
+///
+/// parser.parse(stream_from_tokens(quote!(T: Clone))).unwrap_err();
+/// ```
+#[must_use]
+pub fn where_clause<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, WhereClause, Error = E> + Clone {
+    let lifetime_predicate = lifetime()
+        .then(punct_span(':'))
+        .then(lifetime_bounds())
+        .map(
+            |((lifetime, colon_token), bounds)| WherePredicate::Lifetime {
+                lifetime,
+                colon_token,
+                bounds,
+            },
+        );
+
+    let type_predicate = for_lifetimes()
+        .map(Verbatim::new)
+        .or_not()
+        .then(ty())
+        .then(punct_span(':'))
+        .then(TypeGrammar::new().bounds.or_not())
+        .map(
+            |(((lifetimes, bounded_ty), colon_token), bounds)| WherePredicate::Type {
+                lifetimes,
+                bounded_ty,
+                colon_token,
+                bounds: bounds.unwrap_or_default(),
+            },
+        );
+
+    keyword("where")
+        .map_with_span(|(), span| span)
+        .then(
+            punctuated(lifetime_predicate.or(type_predicate))
+                .or_not()
+                .map(Option::unwrap_or_default),
+        )
+        .map(|(where_token, predicates)| WhereClause {
+            where_token,
+            predicates,
+        })
+        .boxed()
+}
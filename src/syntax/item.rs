@@ -0,0 +1,1328 @@
+//! Items, such as functions, impl blocks, traits and modules
+//!
+//! Items are parsed into a structured form, down to the level attribute macros usually inspect -
+//! function signatures are fully parsed, while function bodies, types and expressions are kept
+//! as raw tokens. Associated items in `impl` and `trait` blocks are also represented as [`Item`]s.
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, TokenStream};
+use quote::{quote, ToTokens};
+
+use super::attr::{inner_attr, outer_attrs, AttrStyle, Attribute};
+use super::expr::ExprGrammar;
+use super::generics::{generics, where_clause, Generics};
+use super::pat::pat_tokens;
+use super::ty::TypeGrammar;
+use super::vis::{visibility, Visibility};
+use super::{
+    group_tokens, joint_token, keyword_span, lifetime, punct_span, punctuated, sym, token, Expr,
+    Lifetime, Pat, Punctuated, Tokens, Type, TypeBound, Verbatim,
+};
+use crate::primitive::{ident, keyword, token_tree};
+use crate::{RustSpan, RustToken};
+
+/// Create a keyword identifier with the given span
+fn kw(name: &str, span: RustSpan) -> Ident {
+    Ident::new(name, *span)
+}
+
+/// Create a path separator, `::`, with the given span
+fn path_sep(span: RustSpan) -> TokenStream {
+    let mut first = Punct::new(':', Spacing::Joint);
+    first.set_span(*span);
+    let mut tokens = TokenStream::new();
+    tokens.extend([first, sym(':', span)]);
+    tokens
+}
+
+/// Create a brace-delimited group with the given contents, spanning from `open` to `close`
+fn braced(contents: TokenStream, open: RustSpan, close: RustSpan) -> Group {
+    let mut group = Group::new(Delimiter::Brace, contents);
+    group.set_span(*open.join_lossy(close));
+    group
+}
+
+/// An item, such as a function, impl block or module
+#[derive(Clone, Debug)]
+pub enum Item {
+    /// A function, such as `pub async fn foo(&self) -> u8 { 1 }`
+    Fn(ItemFn),
+    /// An impl block, such as `impl<T> Trait for Foo<T> { ... }`
+    Impl(ItemImpl),
+    /// A trait, such as `pub trait Foo: Bar { ... }`
+    Trait(ItemTrait),
+    /// A module, such as `mod foo;` or `mod foo { ... }`
+    Mod(ItemMod),
+    /// A use item, such as `use std::{fmt, io::Read as _};`
+    Use(ItemUse),
+    /// A constant, such as `const FOO: u8 = 1;`
+    Const(ItemConst),
+    /// A static, such as `static mut FOO: u8 = 1;`
+    Static(ItemStatic),
+    /// A type alias or associated type, such as `type Foo<T> = Vec<T>;`
+    Type(ItemType),
+    /// Any other item, such as a struct or macro invocation, kept as raw tokens including its
+    /// attributes and visibility
+    Verbatim(Verbatim),
+}
+
+impl Item {
+    /// Get the outer and inner attributes on this item. Verbatim items have none, as their
+    /// attributes are kept in their tokens.
+    #[must_use]
+    pub fn attrs(&self) -> &[Attribute] {
+        match self {
+            Item::Fn(item) => &item.attrs,
+            Item::Impl(item) => &item.attrs,
+            Item::Trait(item) => &item.attrs,
+            Item::Mod(item) => &item.attrs,
+            Item::Use(item) => &item.attrs,
+            Item::Const(item) => &item.attrs,
+            Item::Static(item) => &item.attrs,
+            Item::Type(item) => &item.attrs,
+            Item::Verbatim(_) => &[],
+        }
+    }
+}
+
+impl ToTokens for Item {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Item::Fn(item) => item.to_tokens(tokens),
+            Item::Impl(item) => item.to_tokens(tokens),
+            Item::Trait(item) => item.to_tokens(tokens),
+            Item::Mod(item) => item.to_tokens(tokens),
+            Item::Use(item) => item.to_tokens(tokens),
+            Item::Const(item) => item.to_tokens(tokens),
+            Item::Static(item) => item.to_tokens(tokens),
+            Item::Type(item) => item.to_tokens(tokens),
+            Item::Verbatim(item) => item.to_tokens(tokens),
+        }
+    }
+}
+
+/// A function, or a function declaration without a body such as in a trait
+#[derive(Clone, Debug)]
+pub struct ItemFn {
+    /// The attributes on the function
+    pub attrs: Vec<Attribute>,
+    /// The visibility of the function
+    pub vis: Visibility,
+    /// The signature of the function
+    pub sig: Signature,
+    /// The body of the function including its braces, or `None` if it ended in a `;`
+    pub block: Option<Verbatim>,
+    /// The span of the `;`, if the function has no body
+    pub semi_token: Option<RustSpan>,
+}
+
+impl ToTokens for ItemFn {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ItemFn {
+            attrs,
+            vis,
+            sig,
+            block,
+            semi_token,
+        } = self;
+        let semi_token = semi_token.map(|span| sym(';', span));
+        tokens.extend(quote!(#(#attrs)* #vis #sig #block #semi_token));
+    }
+}
+
+/// The signature of a function, such as `const unsafe fn foo<T>(&self, x: T) -> u8 where T: Copy`
+#[derive(Clone, Debug)]
+pub struct Signature {
+    /// The span of the `const` keyword, if present
+    pub constness: Option<RustSpan>,
+    /// The span of the `async` keyword, if present
+    pub asyncness: Option<RustSpan>,
+    /// The span of the `unsafe` keyword, if present
+    pub unsafety: Option<RustSpan>,
+    /// The ABI, such as `extern "C"`, if present
+    pub abi: Option<Abi>,
+    /// The span of the `fn` keyword
+    pub fn_token: RustSpan,
+    /// The name of the function
+    pub ident: Ident,
+    /// The generics of the function, including its where clause
+    pub generics: Generics,
+    /// The span of the opening `(` of the arguments
+    pub open_token: RustSpan,
+    /// The arguments of the function, including any receiver
+    pub inputs: Punctuated<FnArg, ','>,
+    /// The span of the closing `)` of the arguments
+    pub close_token: RustSpan,
+    /// The return type of the function
+    pub output: ReturnType,
+}
+
+impl Signature {
+    /// Get the receiver of this function, such as `&mut self`, if it has one
+    #[must_use]
+    pub fn receiver(&self) -> Option<&Receiver> {
+        match self.inputs.first()? {
+            FnArg::Receiver(receiver) => Some(receiver),
+            FnArg::Typed(_) => None,
+        }
+    }
+
+    /// Get the span covering this signature, from the first qualifier to the function name
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        self.constness
+            .or(self.asyncness)
+            .or(self.unsafety)
+            .or_else(|| self.abi.as_ref().map(|abi| abi.extern_token))
+            .unwrap_or(self.fn_token)
+            .join_lossy(self.ident.span().into())
+    }
+}
+
+impl ToTokens for Signature {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Signature {
+            constness,
+            asyncness,
+            unsafety,
+            abi,
+            fn_token,
+            ident,
+            generics,
+            open_token,
+            inputs,
+            close_token,
+            output,
+        } = self;
+        let constness = constness.map(|span| kw("const", span));
+        let asyncness = asyncness.map(|span| kw("async", span));
+        let unsafety = unsafety.map(|span| kw("unsafe", span));
+        let fn_token = kw("fn", *fn_token);
+        let mut inputs = Group::new(Delimiter::Parenthesis, inputs.to_token_stream());
+        inputs.set_span(*open_token.join_lossy(*close_token));
+        let where_clause = &generics.where_clause;
+        tokens.extend(quote!(
+            #constness #asyncness #unsafety #abi #fn_token #ident #generics #inputs
+            #output #where_clause
+        ));
+    }
+}
+
+/// An ABI, such as `extern "C"`
+#[derive(Clone, Debug)]
+pub struct Abi {
+    /// The span of the `extern` keyword
+    pub extern_token: RustSpan,
+    /// The name of the ABI, if present
+    pub name: Option<Literal>,
+}
+
+impl ToTokens for Abi {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        kw("extern", self.extern_token).to_tokens(tokens);
+        self.name.to_tokens(tokens);
+    }
+}
+
+/// A function argument
+#[derive(Clone, Debug)]
+pub enum FnArg {
+    /// A receiver, such as `&mut self`
+    Receiver(Receiver),
+    /// An argument with a pattern and type, such as `(a, b): (u8, u8)`
+    Typed(PatType),
+}
+
+impl ToTokens for FnArg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            FnArg::Receiver(receiver) => receiver.to_tokens(tokens),
+            FnArg::Typed(arg) => arg.to_tokens(tokens),
+        }
+    }
+}
+
+/// A receiver, such as `self`, `&'a mut self` or `self: Box<Self>`
+#[derive(Clone, Debug)]
+pub struct Receiver {
+    /// The attributes on the receiver
+    pub attrs: Vec<Attribute>,
+    /// The span of the `&` and the lifetime, if the receiver is a reference
+    pub reference: Option<(RustSpan, Option<Lifetime>)>,
+    /// The span of the `mut` keyword, if present
+    pub mutability: Option<RustSpan>,
+    /// The span of the `self` keyword
+    pub self_token: RustSpan,
+    /// The explicit type of the receiver, with the span of the `:`, if present
+    pub ty: Option<(RustSpan, Type)>,
+}
+
+impl ToTokens for Receiver {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Receiver {
+            attrs,
+            reference,
+            mutability,
+            self_token,
+            ty,
+        } = self;
+        tokens.extend(quote!(#(#attrs)*));
+        if let Some((and_token, lifetime)) = reference {
+            let and_token = sym('&', *and_token);
+            tokens.extend(quote!(#and_token #lifetime));
+        }
+        let mutability = mutability.map(|span| kw("mut", span));
+        let self_token = kw("self", *self_token);
+        tokens.extend(quote!(#mutability #self_token));
+        if let Some((colon_token, ty)) = ty {
+            let colon_token = sym(':', *colon_token);
+            tokens.extend(quote!(#colon_token #ty));
+        }
+    }
+}
+
+/// A typed function argument, such as `mut x: u8`
+#[derive(Clone, Debug)]
+pub struct PatType {
+    /// The attributes on the argument
+    pub attrs: Vec<Attribute>,
+    /// The pattern binding the argument
+    pub pat: Pat,
+    /// The span of the `:`
+    pub colon_token: RustSpan,
+    /// The type of the argument
+    pub ty: Type,
+}
+
+impl ToTokens for PatType {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let PatType {
+            attrs,
+            pat,
+            colon_token,
+            ty,
+        } = self;
+        let colon_token = sym(':', *colon_token);
+        tokens.extend(quote!(#(#attrs)* #pat #colon_token #ty));
+    }
+}
+
+/// The return type of a function
+#[derive(Clone, Debug, Default)]
+pub enum ReturnType {
+    /// No return type was written, so the function returns `()`
+    #[default]
+    Default,
+    /// An explicit return type, with the span of the `->`
+    Type(RustSpan, Type),
+}
+
+impl ToTokens for ReturnType {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if let ReturnType::Type(arrow, ty) = self {
+            let mut dash = Punct::new('-', Spacing::Joint);
+            dash.set_span(**arrow);
+            tokens.extend([dash, sym('>', *arrow)]);
+            ty.to_tokens(tokens);
+        }
+    }
+}
+
+/// An impl block, such as `unsafe impl<T> Send for Foo<T> {}`
+#[derive(Clone, Debug)]
+pub struct ItemImpl {
+    /// The outer attributes on the block, followed by any inner attributes
+    pub attrs: Vec<Attribute>,
+    /// The span of the `unsafe` keyword, if present
+    pub unsafety: Option<RustSpan>,
+    /// The span of the `impl` keyword
+    pub impl_token: RustSpan,
+    /// The generics of the block, including its where clause
+    pub generics: Generics,
+    /// The implemented trait, if this is a trait impl - the span of a negating `!`, the trait,
+    /// and the span of the `for` keyword
+    pub trait_: Option<(Option<RustSpan>, Type, RustSpan)>,
+    /// The type the block is for
+    pub self_ty: Type,
+    /// The span of the opening `{`
+    pub open_token: RustSpan,
+    /// The associated items in the block
+    pub items: Vec<Item>,
+    /// The span of the closing `}`
+    pub close_token: RustSpan,
+}
+
+impl ToTokens for ItemImpl {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ItemImpl {
+            attrs,
+            unsafety,
+            impl_token,
+            generics,
+            trait_,
+            self_ty,
+            open_token,
+            items,
+            close_token,
+        } = self;
+        let outer = attrs.iter().filter(|attr| attr.style == AttrStyle::Outer);
+        let inner = attrs.iter().filter(|attr| attr.style == AttrStyle::Inner);
+        let unsafety = unsafety.map(|span| kw("unsafe", span));
+        let impl_token = kw("impl", *impl_token);
+        tokens.extend(quote!(#(#outer)* #unsafety #impl_token #generics));
+        if let Some((bang, path, for_token)) = trait_ {
+            let bang = bang.map(|span| sym('!', span));
+            let for_token = kw("for", *for_token);
+            tokens.extend(quote!(#bang #path #for_token));
+        }
+        let where_clause = &generics.where_clause;
+        let body = braced(quote!(#(#inner)* #(#items)*), *open_token, *close_token);
+        tokens.extend(quote!(#self_ty #where_clause #body));
+    }
+}
+
+/// A trait, such as `pub unsafe trait Foo<T>: Bar where T: Copy { ... }`
+#[derive(Clone, Debug)]
+pub struct ItemTrait {
+    /// The outer attributes on the trait, followed by any inner attributes
+    pub attrs: Vec<Attribute>,
+    /// The visibility of the trait
+    pub vis: Visibility,
+    /// The span of the `unsafe` keyword, if present
+    pub unsafety: Option<RustSpan>,
+    /// The span of the `auto` keyword, if present
+    pub auto_token: Option<RustSpan>,
+    /// The span of the `trait` keyword
+    pub trait_token: RustSpan,
+    /// The name of the trait
+    pub ident: Ident,
+    /// The generics of the trait, including its where clause
+    pub generics: Generics,
+    /// The span of the `:` before the supertraits, if present
+    pub colon_token: Option<RustSpan>,
+    /// The supertraits of the trait
+    pub supertraits: Punctuated<TypeBound, '+'>,
+    /// The span of the opening `{`
+    pub open_token: RustSpan,
+    /// The associated items in the trait
+    pub items: Vec<Item>,
+    /// The span of the closing `}`
+    pub close_token: RustSpan,
+}
+
+impl ToTokens for ItemTrait {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ItemTrait {
+            attrs,
+            vis,
+            unsafety,
+            auto_token,
+            trait_token,
+            ident,
+            generics,
+            colon_token,
+            supertraits,
+            open_token,
+            items,
+            close_token,
+        } = self;
+        let outer = attrs.iter().filter(|attr| attr.style == AttrStyle::Outer);
+        let inner = attrs.iter().filter(|attr| attr.style == AttrStyle::Inner);
+        let unsafety = unsafety.map(|span| kw("unsafe", span));
+        let auto_token = auto_token.map(|span| kw("auto", span));
+        let trait_token = kw("trait", *trait_token);
+        let colon_token = colon_token.map(|span| sym(':', span));
+        tokens.extend(quote!(
+            #(#outer)* #vis #unsafety #auto_token #trait_token #ident #generics
+            #colon_token #supertraits
+        ));
+        let where_clause = &generics.where_clause;
+        let body = braced(quote!(#(#inner)* #(#items)*), *open_token, *close_token);
+        tokens.extend(quote!(#where_clause #body));
+    }
+}
+
+/// A module, such as `mod foo;` or `pub mod foo { ... }`
+#[derive(Clone, Debug)]
+pub struct ItemMod {
+    /// The outer attributes on the module, followed by any inner attributes
+    pub attrs: Vec<Attribute>,
+    /// The visibility of the module
+    pub vis: Visibility,
+    /// The span of the `unsafe` keyword, if present
+    pub unsafety: Option<RustSpan>,
+    /// The span of the `mod` keyword
+    pub mod_token: RustSpan,
+    /// The name of the module
+    pub ident: Ident,
+    /// The items in the module, with the spans of the opening and closing braces, or `None` if
+    /// it ended in a `;`
+    pub content: Option<(RustSpan, Vec<Item>, RustSpan)>,
+    /// The span of the `;`, if the module has no body
+    pub semi_token: Option<RustSpan>,
+}
+
+impl ToTokens for ItemMod {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ItemMod {
+            attrs,
+            vis,
+            unsafety,
+            mod_token,
+            ident,
+            content,
+            semi_token,
+        } = self;
+        let outer = attrs.iter().filter(|attr| attr.style == AttrStyle::Outer);
+        let inner = attrs.iter().filter(|attr| attr.style == AttrStyle::Inner);
+        let unsafety = unsafety.map(|span| kw("unsafe", span));
+        let mod_token = kw("mod", *mod_token);
+        let body = content
+            .as_ref()
+            .map(|(open, items, close)| braced(quote!(#(#inner)* #(#items)*), *open, *close));
+        let semi_token = semi_token.map(|span| sym(';', span));
+        tokens.extend(quote!(#(#outer)* #vis #unsafety #mod_token #ident #body #semi_token));
+    }
+}
+
+/// A use item, such as `pub use std::{fmt, io::Read as _};`
+#[derive(Clone, Debug)]
+pub struct ItemUse {
+    /// The attributes on the item
+    pub attrs: Vec<Attribute>,
+    /// The visibility of the item
+    pub vis: Visibility,
+    /// The span of the `use` keyword
+    pub use_token: RustSpan,
+    /// The span of the leading `::`, if present
+    pub leading_colon: Option<RustSpan>,
+    /// The tree of imported names
+    pub tree: UseTree,
+    /// The span of the `;`
+    pub semi_token: RustSpan,
+}
+
+impl ToTokens for ItemUse {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ItemUse {
+            attrs,
+            vis,
+            use_token,
+            leading_colon,
+            tree,
+            semi_token,
+        } = self;
+        let use_token = kw("use", *use_token);
+        let leading_colon = leading_colon.map(path_sep);
+        let semi_token = sym(';', *semi_token);
+        tokens.extend(quote!(#(#attrs)* #vis #use_token #leading_colon #tree #semi_token));
+    }
+}
+
+/// A tree of imported names in a use item, such as `io::{self, Read as _}`
+#[derive(Clone, Debug)]
+pub enum UseTree {
+    /// A path segment followed by the span of the `::` and the rest of the tree, such as
+    /// `io::...`
+    Path(Ident, RustSpan, Box<UseTree>),
+    /// A single imported name, such as `Read`
+    Name(Ident),
+    /// A renamed import with the span of the `as`, such as `Read as _`
+    Rename(Ident, RustSpan, Ident),
+    /// A glob import, `*`, with its span
+    Glob(RustSpan),
+    /// A braced group of trees with the spans of its braces, such as `{self, Read as _}`
+    Group(RustSpan, Punctuated<UseTree, ','>, RustSpan),
+}
+
+impl ToTokens for UseTree {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            UseTree::Path(ident, colon2, tree) => {
+                let colon2 = path_sep(*colon2);
+                tokens.extend(quote!(#ident #colon2 #tree));
+            }
+            UseTree::Name(ident) => ident.to_tokens(tokens),
+            UseTree::Rename(ident, as_token, rename) => {
+                let as_token = kw("as", *as_token);
+                tokens.extend(quote!(#ident #as_token #rename));
+            }
+            UseTree::Glob(span) => sym('*', *span).to_tokens(tokens),
+            UseTree::Group(open, trees, close) => {
+                braced(trees.to_token_stream(), *open, *close).to_tokens(tokens);
+            }
+        }
+    }
+}
+
+/// A constant, or an associated constant which may have no value such as in a trait
+#[derive(Clone, Debug)]
+pub struct ItemConst {
+    /// The attributes on the constant
+    pub attrs: Vec<Attribute>,
+    /// The visibility of the constant
+    pub vis: Visibility,
+    /// The span of the `const` keyword
+    pub const_token: RustSpan,
+    /// The name of the constant, which may be `_`
+    pub ident: Ident,
+    /// The span of the `:`
+    pub colon_token: RustSpan,
+    /// The type of the constant
+    pub ty: Type,
+    /// The value of the constant, with the span of the `=`, if present
+    pub value: Option<(RustSpan, Expr)>,
+    /// The span of the `;`
+    pub semi_token: RustSpan,
+}
+
+impl ToTokens for ItemConst {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ItemConst {
+            attrs,
+            vis,
+            const_token,
+            ident,
+            colon_token,
+            ty,
+            value,
+            semi_token,
+        } = self;
+        let const_token = kw("const", *const_token);
+        let colon_token = sym(':', *colon_token);
+        tokens.extend(quote!(#(#attrs)* #vis #const_token #ident #colon_token #ty));
+        if let Some((eq_token, value)) = value {
+            let eq_token = sym('=', *eq_token);
+            tokens.extend(quote!(#eq_token #value));
+        }
+        sym(';', *semi_token).to_tokens(tokens);
+    }
+}
+
+/// A static, such as `static mut FOO: u8 = 1;`
+#[derive(Clone, Debug)]
+pub struct ItemStatic {
+    /// The attributes on the static
+    pub attrs: Vec<Attribute>,
+    /// The visibility of the static
+    pub vis: Visibility,
+    /// The span of the `static` keyword
+    pub static_token: RustSpan,
+    /// The span of the `mut` keyword, if present
+    pub mutability: Option<RustSpan>,
+    /// The name of the static
+    pub ident: Ident,
+    /// The span of the `:`
+    pub colon_token: RustSpan,
+    /// The type of the static
+    pub ty: Type,
+    /// The value of the static, with the span of the `=`, if present
+    pub value: Option<(RustSpan, Expr)>,
+    /// The span of the `;`
+    pub semi_token: RustSpan,
+}
+
+impl ToTokens for ItemStatic {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ItemStatic {
+            attrs,
+            vis,
+            static_token,
+            mutability,
+            ident,
+            colon_token,
+            ty,
+            value,
+            semi_token,
+        } = self;
+        let static_token = kw("static", *static_token);
+        let mutability = mutability.map(|span| kw("mut", span));
+        let colon_token = sym(':', *colon_token);
+        tokens.extend(quote!(#(#attrs)* #vis #static_token #mutability #ident #colon_token #ty));
+        if let Some((eq_token, value)) = value {
+            let eq_token = sym('=', *eq_token);
+            tokens.extend(quote!(#eq_token #value));
+        }
+        sym(';', *semi_token).to_tokens(tokens);
+    }
+}
+
+/// A type alias, or an associated type which may have bounds and no type such as in a trait
+#[derive(Clone, Debug)]
+pub struct ItemType {
+    /// The attributes on the alias
+    pub attrs: Vec<Attribute>,
+    /// The visibility of the alias
+    pub vis: Visibility,
+    /// The span of the `type` keyword
+    pub type_token: RustSpan,
+    /// The name of the alias
+    pub ident: Ident,
+    /// The generics of the alias, including its where clause
+    pub generics: Generics,
+    /// The span of the `:` before the bounds, if present
+    pub colon_token: Option<RustSpan>,
+    /// The bounds on the alias
+    pub bounds: Punctuated<TypeBound, '+'>,
+    /// The aliased type, with the span of the `=`, if present
+    pub ty: Option<(RustSpan, Type)>,
+    /// Whether the where clause comes after the aliased type, as in
+    /// `type X<T> = Y<T> where T: Copy;`, rather than before the `=`
+    pub trailing_where: bool,
+    /// The span of the `;`
+    pub semi_token: RustSpan,
+}
+
+impl ToTokens for ItemType {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ItemType {
+            attrs,
+            vis,
+            type_token,
+            ident,
+            generics,
+            colon_token,
+            bounds,
+            ty,
+            trailing_where,
+            semi_token,
+        } = self;
+        let type_token = kw("type", *type_token);
+        let colon_token = colon_token.map(|span| sym(':', span));
+        let where_clause = &generics.where_clause;
+        tokens.extend(quote!(#(#attrs)* #vis #type_token #ident #generics #colon_token #bounds));
+        if !trailing_where {
+            where_clause.to_tokens(tokens);
+        }
+        if let Some((eq_token, ty)) = ty {
+            let eq_token = sym('=', *eq_token);
+            tokens.extend(quote!(#eq_token #ty));
+        }
+        if *trailing_where {
+            where_clause.to_tokens(tokens);
+        }
+        sym(';', *semi_token).to_tokens(tokens);
+    }
+}
+
+/// Accepts an item, including its attributes
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::item::{item, Item};
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::{quote, ToTokens};
+/// let parser = item::<Cheap<_, RustSpan>>().repeated().then_ignore(end());
+///
+/// let input = quote!(
+///     use std::{fmt, io::Read as _,};
+///
+///     /// Docs
+///     pub const unsafe extern "C" fn foo<'a, T: 'a,>(&'a mut self, (a, b): (u8, T),) -> u8
+///     where
+///         T: Copy,
+///     {
+///         a + 1
+///     }
+///
+///     impl<T> Trait for Foo<T> {
+///         type Out<U> = Vec<U> where U: Copy;
+///         const N: usize = 3;
+///         fn call(self) {}
+///     }
+///
+///     pub(crate) mod inner {
+///         #![allow(unused)]
+///         static mut COUNT: u8 = 0;
+///         pub trait Bar: Clone { fn bar(&self); }
+///     }
+///
+///     struct Other;
+/// );
+/// let items = parser.parse(stream_from_tokens(input.clone())).unwrap();
+/// assert_eq!(items.len(), 5);
+/// assert_eq!(quote!(#(#items)*).to_string(), input.to_string());
+///
+/// match &items[1] {
+///     Item::Fn(item) => {
+///         assert_eq!(item.sig.ident, "foo");
+///         assert!(item.sig.receiver().is_some());
+///         assert_eq!(item.sig.inputs.len(), 2);
+///     }
+///     _ => panic!("Expected a function"),
+/// }
+/// match &items[2] {
+///     Item::Impl(item) => assert_eq!(item.items.len(), 3),
+///     _ => panic!("Expected an impl block"),
+/// }
+/// assert!(matches!(items[4], Item::Verbatim(_)));
+///
+/// parser.parse(stream_from_tokens(quote!(fn foo(x) {}))).unwrap_err();
+/// parser
+///     .parse(stream_from_tokens(quote!(type X<T> where T: Copy = T where T: Copy;)))
+///     .unwrap_err();
+/// ```
+#[must_use]
+pub fn item<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Item, Error = E> + Clone {
+    ItemGrammar::new().item
+}
+
+/// Accepts a function item, including its attributes and body. This is the usual input of an
+/// attribute macro on a function.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::item::item_fn;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::{quote, ToTokens};
+/// let parser = item_fn::<Cheap<_, RustSpan>>().then_ignore(end());
+///
+/// let input = quote!(#[inline] async fn foo(x: u8) -> u8 { x });
+/// let item = parser.parse(stream_from_tokens(input.clone())).unwrap();
+/// assert!(item.sig.asyncness.is_some());
+/// assert_eq!(item.to_token_stream().to_string(), input.to_string());
+///
+/// let errors = item_fn::<RustError>()
+///     .parse(stream_from_tokens(quote!(fn foo();)))
+///     .unwrap_err();
+/// assert_eq!(errors[0].to_string(), "unexpected `;`, expected `-`, `where` or `{`");
+/// ```
+#[must_use]
+pub fn item_fn<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, ItemFn, Error = E> + Clone {
+    ItemGrammar::new().item_fn
+}
+
+/// Accepts a function signature, such as `async fn foo(&self) -> u8`
+#[must_use]
+pub fn signature<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Signature, Error = E> + Clone {
+    ItemGrammar::new().signature
+}
+
+/// The parsers making up the item grammar, built together so they can share the type and
+/// expression grammars
+struct ItemGrammar<E: Error<RustToken, Span = RustSpan> + 'static> {
+    /// Accepts an item
+    item: BoxedParser<'static, RustToken, Item, E>,
+    /// Accepts a function item with a body
+    item_fn: BoxedParser<'static, RustToken, ItemFn, E>,
+    /// Accepts a function signature
+    signature: BoxedParser<'static, RustToken, Signature, E>,
+}
+
+impl<E: Error<RustToken, Span = RustSpan> + 'static> ItemGrammar<E> {
+    /// Build the item grammar
+    fn new() -> ItemGrammar<E> {
+        let types = TypeGrammar::new();
+        let ty = types.ty.clone().map(|tokens| Type(Verbatim::new(tokens)));
+        let expr = ExprGrammar::new()
+            .expr
+            .map(|tokens| Expr(Verbatim::new(tokens)));
+        let semi = punct_span(';');
+
+        let signature = signature_parser(ty.clone().boxed());
+
+        let mut item = Recursive::declare();
+
+        let header = outer_attrs().then(visibility());
+        let inner_attrs = inner_attr().repeated();
+
+        let fn_body = group_tokens(Delimiter::Brace).map(Verbatim::new);
+
+        let item_fn = header
+            .clone()
+            .then(signature.clone())
+            .then(
+                fn_body
+                    .clone()
+                    .map(|block| (Some(block), None))
+                    .or(semi.clone().map(|semi_token| (None, Some(semi_token)))),
+            )
+            .map(|(((attrs, vis), sig), (block, semi_token))| {
+                Item::Fn(ItemFn {
+                    attrs,
+                    vis,
+                    sig,
+                    block,
+                    semi_token,
+                })
+            });
+
+        // Functions given to an attribute macro must have a body, so a `;` is reported as an
+        // unexpected token rather than accepted
+        let item_fn_with_body = header
+            .clone()
+            .then(signature.clone())
+            .then(fn_body)
+            .map(|(((attrs, vis), sig), block)| ItemFn {
+                attrs,
+                vis,
+                sig,
+                block: Some(block),
+                semi_token: None,
+            })
+            .boxed();
+
+        // The spans of the braces, and the inner attributes and items between them
+        let braced_items = just(RustToken::StartDelim(Delimiter::Brace))
+            .map_with_span(|_, span: RustSpan| span)
+            .then(inner_attrs.clone().then(item.clone().repeated()))
+            .then(just(RustToken::EndDelim(Delimiter::Brace)).map_with_span(|_, span| span))
+            .map(|((open, (inner, items)), close)| (open, inner, items, close));
+
+        // The `:` and bounds of a trait or associated type
+        let bounds = punct_span(':')
+            .then(types.bounds.clone().or_not())
+            .or_not()
+            .map(|bounds| match bounds {
+                Some((colon_token, bounds)) => (Some(colon_token), bounds.unwrap_or_default()),
+                None => (None, Punctuated::new()),
+            });
+
+        let item_impl = outer_attrs()
+            .then(keyword_span("unsafe").or_not())
+            .then(keyword_span("impl"))
+            .then(generics())
+            .then(
+                punct_span('!')
+                    .or_not()
+                    .then(ty.clone())
+                    .then(keyword_span("for"))
+                    .map(|((bang, path), for_token)| (bang, path, for_token))
+                    .or_not(),
+            )
+            .then(ty.clone())
+            .then(where_clause().or_not())
+            .then(braced_items.clone())
+            .map(
+                |(
+                    ((((((mut attrs, unsafety), impl_token), mut generics), trait_), self_ty), wc),
+                    (open_token, inner, items, close_token),
+                )| {
+                    generics.where_clause = wc;
+                    attrs.extend(inner);
+                    Item::Impl(ItemImpl {
+                        attrs,
+                        unsafety,
+                        impl_token,
+                        generics,
+                        trait_,
+                        self_ty,
+                        open_token,
+                        items,
+                        close_token,
+                    })
+                },
+            );
+
+        let item_trait = header
+            .clone()
+            .then(keyword_span("unsafe").or_not())
+            .then(keyword_span("auto").or_not())
+            .then(keyword_span("trait"))
+            .then(ident())
+            .then(generics())
+            .then(bounds.clone())
+            .then(where_clause().or_not())
+            .then(braced_items.clone())
+            .map(
+                |(
+                    (
+                        (
+                            (
+                                (((((mut attrs, vis), unsafety), auto_token), trait_token), ident),
+                                mut generics,
+                            ),
+                            (colon_token, supertraits),
+                        ),
+                        wc,
+                    ),
+                    (open_token, inner, items, close_token),
+                )| {
+                    generics.where_clause = wc;
+                    attrs.extend(inner);
+                    Item::Trait(ItemTrait {
+                        attrs,
+                        vis,
+                        unsafety,
+                        auto_token,
+                        trait_token,
+                        ident,
+                        generics,
+                        colon_token,
+                        supertraits,
+                        open_token,
+                        items,
+                        close_token,
+                    })
+                },
+            );
+
+        let item_mod = header
+            .clone()
+            .then(keyword_span("unsafe").or_not())
+            .then(keyword_span("mod"))
+            .then(ident())
+            .then(
+                braced_items
+                    .map(|content| (Some(content), None))
+                    .or(semi.clone().map(|semi_token| (None, Some(semi_token)))),
+            )
+            .map(
+                |(((((mut attrs, vis), unsafety), mod_token), ident), (content, semi_token))| {
+                    let content = content.map(|(open, inner, items, close)| {
+                        attrs.extend(inner);
+                        (open, items, close)
+                    });
+                    Item::Mod(ItemMod {
+                        attrs,
+                        vis,
+                        unsafety,
+                        mod_token,
+                        ident,
+                        content,
+                        semi_token,
+                    })
+                },
+            );
+
+        let item_use = header
+            .clone()
+            .then(keyword_span("use"))
+            .then(path_sep_span().or_not())
+            .then(use_tree())
+            .then(semi.clone())
+            .map(
+                |(((((attrs, vis), use_token), leading_colon), tree), semi_token)| {
+                    Item::Use(ItemUse {
+                        attrs,
+                        vis,
+                        use_token,
+                        leading_colon,
+                        tree,
+                        semi_token,
+                    })
+                },
+            );
+
+        // `ident` accepts `_`, as it isn't a keyword
+        let item_const = header
+            .clone()
+            .then(keyword_span("const"))
+            .then(ident())
+            .then(punct_span(':'))
+            .then(ty.clone())
+            .then(punct_span('=').then(expr.clone()).or_not())
+            .then(semi.clone())
+            .map(
+                |(
+                    ((((((attrs, vis), const_token), ident), colon_token), ty), value),
+                    semi_token,
+                )| {
+                    Item::Const(ItemConst {
+                        attrs,
+                        vis,
+                        const_token,
+                        ident,
+                        colon_token,
+                        ty,
+                        value,
+                        semi_token,
+                    })
+                },
+            );
+
+        let item_static = header
+            .clone()
+            .then(keyword_span("static"))
+            .then(keyword_span("mut").or_not())
+            .then(ident())
+            .then(punct_span(':'))
+            .then(ty.clone())
+            .then(punct_span('=').then(expr).or_not())
+            .then(semi.clone())
+            .map(
+                |(
+                    (
+                        ((((((attrs, vis), static_token), mutability), ident), colon_token), ty),
+                        value,
+                    ),
+                    semi_token,
+                )| {
+                    Item::Static(ItemStatic {
+                        attrs,
+                        vis,
+                        static_token,
+                        mutability,
+                        ident,
+                        colon_token,
+                        ty,
+                        value,
+                        semi_token,
+                    })
+                },
+            );
+
+        let item_type = header
+            .clone()
+            .then(keyword_span("type"))
+            .then(ident())
+            .then(generics())
+            .then(bounds)
+            .then(where_clause().or_not())
+            .then(punct_span('=').then(ty).or_not())
+            .then(where_clause().or_not())
+            .then(semi)
+            .try_map(
+                |(
+                    (
+                        (
+                            (
+                                (
+                                    ((((attrs, vis), type_token), ident), mut generics),
+                                    (colon_token, bounds),
+                                ),
+                                wc,
+                            ),
+                            ty,
+                        ),
+                        trailing_wc,
+                    ),
+                    semi_token,
+                ),
+                 _| {
+                    // The where clause may come before or after the `=`, but not both
+                    let trailing_where = trailing_wc.is_some();
+                    generics.where_clause = match (wc, trailing_wc) {
+                        (Some(_), Some(trailing)) => {
+                            return Err(E::expected_input_found(
+                                trailing.where_token,
+                                [Some(RustToken::Punct(Punct::new(';', Spacing::Alone)))],
+                                Some(RustToken::Ident(kw("where", trailing.where_token))),
+                            ));
+                        }
+                        (wc, trailing) => wc.or(trailing),
+                    };
+                    Ok(Item::Type(ItemType {
+                        attrs,
+                        vis,
+                        type_token,
+                        ident,
+                        generics,
+                        colon_token,
+                        bounds,
+                        ty,
+                        trailing_where,
+                        semi_token,
+                    }))
+                },
+            );
+
+        item.define(
+            choice((
+                item_fn,
+                item_impl,
+                item_trait,
+                item_mod,
+                item_use,
+                item_const,
+                item_static,
+                item_type,
+            ))
+            .or(verbatim_item())
+            .boxed(),
+        );
+
+        ItemGrammar {
+            item: item.boxed(),
+            item_fn: item_fn_with_body,
+            signature: signature.boxed(),
+        }
+    }
+}
+
+/// Accepts a function signature, using the provided type parser
+fn signature_parser<E: Error<RustToken, Span = RustSpan> + 'static>(
+    ty: BoxedParser<'static, RustToken, Type, E>,
+) -> impl Parser<RustToken, Signature, Error = E> + Clone {
+    let abi = keyword_span("extern")
+        .then(filter_map(RustToken::filter_literal).or_not())
+        .map(|(extern_token, name)| Abi { extern_token, name });
+
+    let receiver = outer_attrs()
+        .then(punct_span('&').then(lifetime().or_not()).or_not())
+        .then(keyword_span("mut").or_not())
+        .then(keyword_span("self"))
+        .then(punct_span(':').then(ty.clone()).or_not())
+        .map(|((((attrs, reference), mutability), self_token), ty)| {
+            FnArg::Receiver(Receiver {
+                attrs,
+                reference,
+                mutability,
+                self_token,
+                ty,
+            })
+        });
+
+    let typed = outer_attrs()
+        .then(pat_tokens(true).map(|tokens| Pat(Verbatim::new(tokens))))
+        .then(punct_span(':'))
+        .then(ty.clone())
+        .map(|(((attrs, pat), colon_token), ty)| {
+            FnArg::Typed(PatType {
+                attrs,
+                pat,
+                colon_token,
+                ty,
+            })
+        });
+
+    let arrow = joint_token('-')
+        .then(punct_span('>'))
+        .map(|((_, dash), gt)| dash.join_lossy(gt));
+
+    keyword_span("const")
+        .or_not()
+        .then(keyword_span("async").or_not())
+        .then(keyword_span("unsafe").or_not())
+        .then(abi.or_not())
+        .then(keyword_span("fn"))
+        .then(ident())
+        .then(generics())
+        .then(just(RustToken::StartDelim(Delimiter::Parenthesis)).map_with_span(|_, span| span))
+        .then(
+            punctuated(receiver.or(typed))
+                .or_not()
+                .map(Option::unwrap_or_default),
+        )
+        .then(just(RustToken::EndDelim(Delimiter::Parenthesis)).map_with_span(|_, span| span))
+        .then(arrow.then(ty).or_not())
+        .then(where_clause().or_not())
+        .map(
+            |(
+                (
+                    (
+                        (
+                            (
+                                (
+                                    (((((constness, asyncness), unsafety), abi), fn_token), ident),
+                                    mut generics,
+                                ),
+                                open_token,
+                            ),
+                            inputs,
+                        ),
+                        close_token,
+                    ),
+                    output,
+                ),
+                wc,
+            )| {
+                generics.where_clause = wc;
+                Signature {
+                    constness,
+                    asyncness,
+                    unsafety,
+                    abi,
+                    fn_token,
+                    ident,
+                    generics,
+                    open_token,
+                    inputs,
+                    close_token,
+                    output: output.map_or(ReturnType::Default, |(arrow, ty)| {
+                        ReturnType::Type(arrow, ty)
+                    }),
+                }
+            },
+        )
+}
+
+/// Accepts a path separator, `::`, keeping its span
+fn path_sep_span<E: Error<RustToken, Span = RustSpan>>(
+) -> impl Parser<RustToken, RustSpan, Error = E> + Clone {
+    joint_token(':')
+        .then(punct_span(':'))
+        .map(|((_, first), second)| first.join_lossy(second))
+}
+
+/// Accepts the tree of a use item, such as `std::{fmt, io::Read as _}`
+fn use_tree<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, UseTree, Error = E> + Clone {
+    recursive(|tree| {
+        // `self`, `super` and `crate` may appear in use paths, and `_` when renaming
+        let name = filter_map(RustToken::filter_ident);
+
+        let path = name
+            .then(path_sep_span())
+            .then(tree.clone())
+            .map(|((ident, colon2), tree)| UseTree::Path(ident, colon2, Box::new(tree)));
+
+        let rename = name
+            .then(keyword_span("as"))
+            .then(name)
+            .map(|((ident, as_token), rename)| UseTree::Rename(ident, as_token, rename));
+
+        let glob = punct_span('*').map(UseTree::Glob);
+
+        let group = just(RustToken::StartDelim(Delimiter::Brace))
+            .map_with_span(|_, span: RustSpan| span)
+            .then(punctuated(tree).or_not().map(Option::unwrap_or_default))
+            .then(just(RustToken::EndDelim(Delimiter::Brace)).map_with_span(|_, span| span))
+            .map(|((open, trees), close)| UseTree::Group(open, trees, close));
+
+        choice((path, rename, name.map(UseTree::Name), glob, group))
+    })
+}
+
+/// Keywords which start an item parsed into a structured form, after any qualifiers
+const ITEM_KEYWORDS: &[&str] = &[
+    "fn", "impl", "trait", "mod", "use", "const", "static", "type", "async", "auto",
+];
+
+/// Accepts any other item, as raw tokens: everything up to and including a `;` or a braced group.
+/// Items which start like a structured item aren't accepted, so errors in them are reported.
+fn verbatim_item<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Item, Error = E> + Clone {
+    let qualifiers = keyword("unsafe")
+        .or(keyword("safe"))
+        .or(keyword("extern").then_ignore(filter_map(RustToken::filter_literal).or_not()))
+        .repeated();
+
+    let guard = outer_attrs()
+        .then(visibility())
+        .then(qualifiers)
+        .then(token(None, |tok| {
+            !tok.as_ident()
+                .is_some_and(|ident| ITEM_KEYWORDS.iter().any(|kw| ident == kw))
+        }))
+        .rewind();
+
+    let end = token(None, |tok| {
+        tok.as_punct().is_some_and(|p| p.as_char() == ';')
+    })
+    .map(|tok| vec![tok])
+    .or(group_tokens(Delimiter::Brace));
+
+    let other = token(None, |tok| {
+        !tok.is_end_delim()
+            && tok.as_punct().is_none_or(|p| p.as_char() != ';')
+            && tok.as_start_delim() != Some(&Delimiter::Brace)
+    })
+    .rewind()
+    .ignore_then(token_tree());
+
+    guard
+        .ignore_then(other.repeated().at_least(1).flatten())
+        .chain::<(RustToken, RustSpan), Tokens, _>(end)
+        .map(|tokens| Item::Verbatim(Verbatim::new(tokens)))
+}
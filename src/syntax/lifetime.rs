@@ -0,0 +1,97 @@
+//! Lifetimes, such as `'a` or `'static`
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use proc_macro2::{Ident, Punct, Spacing, TokenStream};
+use quote::ToTokens;
+use std::fmt;
+
+use super::{joint_token, Tokens};
+use crate::{RustSpan, RustToken};
+
+/// A lifetime, such as `'a`. Rust tokenizes these as a joint `'` followed by an identifier.
+#[derive(Clone, Debug)]
+pub struct Lifetime {
+    /// The span of the leading apostrophe
+    pub apostrophe: RustSpan,
+    /// The name of the lifetime, without the apostrophe
+    pub ident: Ident,
+}
+
+impl Lifetime {
+    /// Get the span of this lifetime, from the apostrophe to the end of the name
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        self.apostrophe.join_lossy(self.ident.span().into())
+    }
+
+    /// Get the flat tokens making up this lifetime
+    pub(crate) fn tokens(&self) -> Tokens {
+        vec![
+            (RustToken::Punct(self.apostrophe_punct()), self.apostrophe),
+            (
+                RustToken::Ident(self.ident.clone()),
+                self.ident.span().into(),
+            ),
+        ]
+    }
+
+    /// Build the apostrophe token for this lifetime
+    fn apostrophe_punct(&self) -> Punct {
+        let mut punct = Punct::new('\'', Spacing::Joint);
+        punct.set_span(*self.apostrophe);
+        punct
+    }
+}
+
+impl PartialEq for Lifetime {
+    fn eq(&self, other: &Self) -> bool {
+        self.ident == other.ident
+    }
+}
+
+impl Eq for Lifetime {}
+
+impl fmt::Display for Lifetime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}", self.ident)
+    }
+}
+
+impl ToTokens for Lifetime {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.apostrophe_punct().to_tokens(tokens);
+        self.ident.to_tokens(tokens);
+    }
+}
+
+/// Accepts a lifetime, such as `'a` or `'static`
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::lifetime;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = lifetime::<Cheap<_, RustSpan>>();
+///
+/// let lt = parser.parse(stream_from_tokens(quote!('static))).unwrap();
+/// assert_eq!(lt.to_string(), "'static");
+///
+/// parser.parse(stream_from_tokens(quote!(a))).unwrap_err();
+/// ```
+#[must_use]
+pub fn lifetime<E: Error<RustToken, Span = RustSpan>>(
+) -> impl Parser<RustToken, Lifetime, Error = E> + Clone {
+    joint_token('\'')
+        .then(filter_map(RustToken::filter_ident))
+        .map(|((_, apostrophe), ident)| Lifetime { apostrophe, ident })
+}
+
+/// Accepts a lifetime, keeping its tokens
+pub(crate) fn lifetime_tokens<E: Error<RustToken, Span = RustSpan>>(
+) -> impl Parser<RustToken, Tokens, Error = E> + Clone {
+    lifetime().map(|lt| lt.tokens())
+}
@@ -0,0 +1,171 @@
+//! Macro invocations, such as `vec![1, 2, 3]` or `sql! { SELECT * FROM users }`
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use chumsky::Stream;
+use proc_macro2::{Delimiter, Group, Punct, Spacing, TokenStream};
+use quote::ToTokens;
+
+use super::path::{path, Path};
+use super::{punct_span, Verbatim};
+use crate::primitive::token_tree;
+use crate::utils::is_keyword;
+use crate::{RustSpan, RustToken};
+
+/// A macro invocation, such as `path!(...)`, `path![...]` or `path! { ... }`
+#[derive(Clone, Debug)]
+pub struct MacroCall {
+    /// The path of the invoked macro
+    pub path: Path,
+    /// The span of the `!`
+    pub bang_token: RustSpan,
+    /// The delimiter around the body
+    pub delimiter: Delimiter,
+    /// The span of the opening delimiter
+    pub open_token: RustSpan,
+    /// The span of the closing delimiter
+    pub close_token: RustSpan,
+    /// The tokens of the body, without the delimiters
+    pub body: Verbatim,
+}
+
+impl MacroCall {
+    /// Get the span covering this whole invocation, from the path to the closing delimiter
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        self.path.span().join_lossy(self.close_token)
+    }
+
+    /// Get the body of this invocation as a `TokenStream`, without the delimiters
+    #[must_use]
+    pub fn body_stream(&self) -> TokenStream {
+        self.body.to_token_stream()
+    }
+
+    /// Get the flat tokens of the body of this invocation, without the delimiters
+    #[must_use]
+    pub fn body_tokens(&self) -> &[(RustToken, RustSpan)] {
+        self.body.tokens()
+    }
+
+    /// Run a parser over the body of this invocation, which must consume all of it. Running out
+    /// of input is reported at the closing delimiter.
+    ///
+    /// # Errors
+    ///
+    /// If the parser fails, or doesn't consume the whole body
+    pub fn parse_body_with<O, P>(&self, parser: P) -> Result<O, Vec<P::Error>>
+    where
+        P: Parser<RustToken, O>,
+        P::Error: Error<RustToken, Span = RustSpan>,
+    {
+        parser.then_ignore(end()).parse(Stream::from_iter(
+            self.close_token,
+            self.body.tokens().iter().cloned(),
+        ))
+    }
+}
+
+impl ToTokens for MacroCall {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.path.to_tokens(tokens);
+        let mut bang = Punct::new('!', Spacing::Alone);
+        bang.set_span(*self.bang_token);
+        bang.to_tokens(tokens);
+        let mut group = Group::new(self.delimiter, self.body_stream());
+        group.set_span(*self.open_token.join_lossy(self.close_token));
+        group.to_tokens(tokens);
+    }
+}
+
+/// Accepts a macro invocation with any delimiter, such as `sql!(...)` or `my::mac! { ... }`.
+/// Keywords aren't accepted in the path, except for a leading `crate`, `self` or `super`.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::mac::macro_call;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use proc_macro2::Delimiter;
+/// # use quote::quote;
+/// let parser = macro_call::<Cheap<_, RustSpan>>().then_ignore(end());
+///
+/// let call = parser
+///     .parse(stream_from_tokens(quote!(query! { sql!(SELECT 1), args })))
+///     .unwrap();
+/// assert!(call.path.is("query"));
+/// assert_eq!(call.delimiter, Delimiter::Brace);
+///
+/// // Find the nested `sql!` invocation in the body
+/// let (nested, _) = call
+///     .parse_body_with(
+///         macro_call::<Cheap<_, RustSpan>>()
+///             .then(any().repeated()),
+///     )
+///     .unwrap();
+/// assert!(nested.path.is("sql"));
+/// assert_eq!(nested.body_stream().to_string(), quote!(SELECT 1).to_string());
+///
+/// parser.parse(stream_from_tokens(quote!(query {}))).unwrap_err();
+///
+/// let call = parser.parse(stream_from_tokens(quote!(super::super::mac!()))).unwrap();
+/// assert!(call.path.is("super::super::mac"));
+///
+/// // An `if` expression, not a call of a macro named `if`
+/// macro_call::<Cheap<_, RustSpan>>()
+///     .parse(stream_from_tokens(quote!(if !(x) {})))
+///     .unwrap_err();
+/// ```
+#[must_use]
+pub fn macro_call<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, MacroCall, Error = E> + Clone {
+    let open = filter_map(|span, tok: RustToken| {
+        tok.into_start_delim()
+            .map_err(|tok| E::expected_input_found(span, [], Some(tok)))
+    })
+    .map_with_span(|delimiter, span| (delimiter, span));
+
+    let close = filter_map(|span, tok: RustToken| {
+        tok.into_end_delim()
+            .map_err(|tok| E::expected_input_found(span, [], Some(tok)))
+    })
+    .map_with_span(|_, span| span);
+
+    // `crate`, `self` and `super` may only lead the path, and only `super` may follow them
+    let path = path().try_map(|path: Path, _| {
+        let mut in_prefix = path.leading_colon.is_none();
+        for (idx, segment) in path.segments.iter().enumerate() {
+            let name = segment.to_string();
+            in_prefix &= match &*name {
+                "crate" | "self" => idx == 0,
+                "super" => true,
+                _ => false,
+            };
+            if is_keyword(&name) && !in_prefix {
+                return Err(E::expected_input_found(
+                    segment.span().into(),
+                    [],
+                    Some(RustToken::Ident(segment.clone())),
+                ));
+            }
+        }
+        Ok(path)
+    });
+
+    path.then(punct_span('!'))
+        .then(open)
+        .then(token_tree().repeated().flatten())
+        .then(close)
+        .map(
+            |((((path, bang_token), (delimiter, open_token)), body), close_token)| MacroCall {
+                path,
+                bang_token,
+                delimiter,
+                open_token,
+                close_token,
+                body: Verbatim::new(body),
+            },
+        )
+}
@@ -0,0 +1,422 @@
+//! Parsers for common pieces of Rust syntax, such as generics and types
+//!
+//! Parts of the syntax which macros usually re-emit rather than inspect, such as types, are kept
+//! as [`Verbatim`] runs of tokens, while everything else is parsed into a structured form.
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use proc_macro2::{Delimiter, Punct, Spacing, TokenStream};
+use quote::ToTokens;
+
+use crate::primitive::token_tree;
+use crate::utils::{from_slice, into_vec, span_of};
+use crate::{RustSpan, RustToken};
+
+pub mod args;
+pub mod attr;
+pub mod doc;
+pub mod expr;
+pub mod generics;
+pub mod item;
+mod lifetime;
+pub mod mac;
+pub mod pat;
+pub mod path;
+pub mod stmt;
+pub mod ty;
+pub mod vis;
+
+pub use attr::{AttrStyle, Attribute, Meta};
+pub use doc::{DocComment, Docs};
+pub use expr::Expr;
+pub use item::Item;
+pub(crate) use lifetime::lifetime_tokens;
+pub use lifetime::{lifetime, Lifetime};
+pub use mac::MacroCall;
+pub use pat::Pat;
+pub use path::Path;
+pub use stmt::{Block, Stmt};
+pub use ty::{Type, TypeBound};
+pub use vis::Visibility;
+
+/// A run of flat tokens and their spans, as recorded by the parsers in this module
+pub(crate) type Tokens = Vec<(RustToken, RustSpan)>;
+
+/// A run of flat tokens, kept exactly as they appeared in the input
+#[derive(Clone, Debug, Default)]
+pub struct Verbatim {
+    /// The recorded tokens, alongside their spans
+    tokens: Tokens,
+}
+
+impl Verbatim {
+    /// Create a new verbatim run from flat tokens. The tokens must have balanced delimiters.
+    #[must_use]
+    pub fn new(tokens: Vec<(RustToken, RustSpan)>) -> Verbatim {
+        Verbatim { tokens }
+    }
+
+    /// Get the recorded tokens, alongside their spans
+    #[must_use]
+    pub fn tokens(&self) -> &[(RustToken, RustSpan)] {
+        &self.tokens
+    }
+
+    /// Convert this into the recorded tokens, alongside their spans
+    #[must_use]
+    pub fn into_tokens(self) -> Vec<(RustToken, RustSpan)> {
+        self.tokens
+    }
+
+    /// Returns whether this run contains no tokens
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Get the span covering all recorded tokens, or the call site if there are none
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        span_of(&self.tokens).unwrap_or_else(|| proc_macro2::Span::call_site().into())
+    }
+}
+
+impl From<TokenStream> for Verbatim {
+    fn from(stream: TokenStream) -> Self {
+        Verbatim::new(into_vec(stream))
+    }
+}
+
+impl ToTokens for Verbatim {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(from_slice(&self.tokens));
+    }
+}
+
+/// A list of items separated by the punctuation character `P`, such as the `,`-separated
+/// parameters of a function, which keeps the span of each separator so it can be re-emitted
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::Punctuated;
+/// # use chumsky_proc::syntax::generics::where_clause;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::{quote, ToTokens};
+/// let input = quote!(where T: Clone + Copy, U: Default,);
+/// let clause = where_clause::<Cheap<_, RustSpan>>()
+///     .parse(stream_from_tokens(input.clone()))
+///     .unwrap();
+/// assert_eq!(clause.predicates.len(), 2);
+/// assert!(clause.predicates.trailing_punct());
+/// assert_eq!(clause.to_token_stream().to_string(), input.to_string());
+///
+/// let mut list: Punctuated<u8, '+'> = [1, 2].into_iter().collect();
+/// list.push(3);
+/// assert_eq!(list.to_token_stream().to_string(), "1u8 + 2u8 + 3u8");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Punctuated<T, const P: char> {
+    /// The items, each alongside the span of the separator following it, if there was one
+    pairs: Vec<(T, Option<RustSpan>)>,
+}
+
+impl<T, const P: char> Punctuated<T, P> {
+    /// Create an empty list
+    #[must_use]
+    pub fn new() -> Punctuated<T, P> {
+        Punctuated { pairs: Vec::new() }
+    }
+
+    /// Get the number of items in the list
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns whether the list contains no items
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Get the first item in the list
+    #[must_use]
+    pub fn first(&self) -> Option<&T> {
+        self.pairs.first().map(|(item, _)| item)
+    }
+
+    /// Get the last item in the list
+    #[must_use]
+    pub fn last(&self) -> Option<&T> {
+        self.pairs.last().map(|(item, _)| item)
+    }
+
+    /// Iterate over the items in the list
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.pairs.iter().map(|(item, _)| item)
+    }
+
+    /// Iterate mutably over the items in the list
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.pairs.iter_mut().map(|(item, _)| item)
+    }
+
+    /// Iterate over the items in the list, alongside the span of the separator following each one
+    pub fn pairs(&self) -> impl Iterator<Item = (&T, Option<RustSpan>)> {
+        self.pairs.iter().map(|(item, punct)| (item, *punct))
+    }
+
+    /// Add an item to the end of the list. Separators without a recorded span are emitted at the
+    /// call site.
+    pub fn push(&mut self, item: T) {
+        self.pairs.push((item, None));
+    }
+
+    /// Add an item to the end of the list, alongside the span of the separator following it
+    pub fn push_pair(&mut self, item: T, punct: Option<RustSpan>) {
+        self.pairs.push((item, punct));
+    }
+
+    /// Returns whether the list ends with a separator
+    #[must_use]
+    pub fn trailing_punct(&self) -> bool {
+        self.pairs.last().is_some_and(|(_, punct)| punct.is_some())
+    }
+}
+
+impl<T, const P: char> Default for Punctuated<T, P> {
+    fn default() -> Self {
+        Punctuated::new()
+    }
+}
+
+impl<T, const P: char> FromIterator<T> for Punctuated<T, P> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = Punctuated::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T, const P: char> Extend<T> for Punctuated<T, P> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.pairs.extend(iter.into_iter().map(|item| (item, None)));
+    }
+}
+
+impl<T, const P: char> IntoIterator for Punctuated<T, P> {
+    type Item = T;
+    type IntoIter =
+        std::iter::Map<std::vec::IntoIter<(T, Option<RustSpan>)>, fn((T, Option<RustSpan>)) -> T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let item: fn((T, Option<RustSpan>)) -> T = |(item, _)| item;
+        self.pairs.into_iter().map(item)
+    }
+}
+
+impl<T: ToTokens, const P: char> ToTokens for Punctuated<T, P> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let last = self.pairs.len().saturating_sub(1);
+        for (i, (item, punct)) in self.pairs.iter().enumerate() {
+            item.to_tokens(tokens);
+            if i < last || punct.is_some() {
+                let span = punct.unwrap_or_else(|| proc_macro2::Span::call_site().into());
+                sym(P, span).to_tokens(tokens);
+            }
+        }
+    }
+}
+
+/// Define a newtype around [`Verbatim`] for a piece of syntax which is kept as raw tokens
+macro_rules! verbatim_node {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug)]
+        pub struct $name(pub $crate::syntax::Verbatim);
+
+        impl $name {
+            /// Get the span covering this node
+            #[must_use]
+            pub fn span(&self) -> $crate::RustSpan {
+                self.0.span()
+            }
+
+            /// Get the flat tokens making up this node, alongside their spans
+            #[must_use]
+            pub fn tokens(&self) -> &[($crate::RustToken, $crate::RustSpan)] {
+                self.0.tokens()
+            }
+        }
+
+        impl From<proc_macro2::TokenStream> for $name {
+            fn from(stream: proc_macro2::TokenStream) -> Self {
+                $name($crate::syntax::Verbatim::from(stream))
+            }
+        }
+
+        impl quote::ToTokens for $name {
+            fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+                self.0.to_tokens(tokens);
+            }
+        }
+    };
+}
+
+pub(crate) use verbatim_node;
+
+/// Create a single punctuation token with the given span
+pub(crate) fn sym(c: char, span: RustSpan) -> Punct {
+    let mut punct = Punct::new(c, Spacing::Alone);
+    punct.set_span(*span);
+    punct
+}
+
+/// Accepts a single token matching `pred`, keeping the token and its span
+pub(crate) fn token<E: Error<RustToken, Span = RustSpan>>(
+    expected: Option<RustToken>,
+    pred: impl Fn(&RustToken) -> bool + Clone,
+) -> impl Parser<RustToken, (RustToken, RustSpan), Error = E> + Clone {
+    filter_map(move |span, tok: RustToken| {
+        if pred(&tok) {
+            Ok((tok, span))
+        } else {
+            Err(E::expected_input_found(
+                span,
+                expected.clone().map(Some),
+                Some(tok),
+            ))
+        }
+    })
+}
+
+/// Accepts a single punctuation character with any spacing, keeping the token
+pub(crate) fn punct_token<E: Error<RustToken, Span = RustSpan>>(
+    c: char,
+) -> impl Parser<RustToken, (RustToken, RustSpan), Error = E> + Clone {
+    token(
+        Some(RustToken::Punct(Punct::new(c, Spacing::Alone))),
+        move |tok| tok.as_punct().is_some_and(|p| p.as_char() == c),
+    )
+}
+
+/// Accepts a single punctuation character joined to the next token, keeping the token
+pub(crate) fn joint_token<E: Error<RustToken, Span = RustSpan>>(
+    c: char,
+) -> impl Parser<RustToken, (RustToken, RustSpan), Error = E> + Clone {
+    token(
+        Some(RustToken::Punct(Punct::new(c, Spacing::Joint))),
+        move |tok| {
+            tok.as_punct()
+                .is_some_and(|p| p.as_char() == c && p.spacing() == Spacing::Joint)
+        },
+    )
+}
+
+/// Accepts an exact identifier, keeping the token
+pub(crate) fn keyword_token<E: Error<RustToken, Span = RustSpan>>(
+    keyword: &'static str,
+) -> impl Parser<RustToken, (RustToken, RustSpan), Error = E> + Clone {
+    token(
+        Some(RustToken::Ident(proc_macro2::Ident::new(
+            keyword,
+            proc_macro2::Span::mixed_site(),
+        ))),
+        move |tok| tok.as_ident().is_some_and(|i| i == keyword),
+    )
+}
+
+/// Accepts an exact identifier, keeping only its span
+pub(crate) fn keyword_span<E: Error<RustToken, Span = RustSpan> + 'static>(
+    keyword: &'static str,
+) -> impl Parser<RustToken, RustSpan, Error = E> + Clone {
+    crate::primitive::keyword(keyword).map_with_span(|(), span| span)
+}
+
+/// Accepts a single punctuation character with any spacing, keeping only its span
+pub(crate) fn punct_span<E: Error<RustToken, Span = RustSpan>>(
+    c: char,
+) -> impl Parser<RustToken, RustSpan, Error = E> + Clone {
+    crate::primitive::punct(c).map_with_span(|(), span| span)
+}
+
+/// Accepts any literal, keeping the token
+pub(crate) fn literal_token<E: Error<RustToken, Span = RustSpan>>(
+) -> impl Parser<RustToken, (RustToken, RustSpan), Error = E> + Clone {
+    token(None, RustToken::is_literal)
+}
+
+/// Accepts a whole group with the given delimiter, keeping all its tokens
+pub(crate) fn group_tokens<E: Error<RustToken, Span = RustSpan> + 'static>(
+    delim: Delimiter,
+) -> impl Parser<RustToken, Tokens, Error = E> + Clone {
+    token(Some(RustToken::StartDelim(delim)), move |tok| {
+        tok.as_start_delim() == Some(&delim)
+    })
+    .rewind()
+    .ignore_then(token_tree())
+}
+
+/// Accepts a whole group with any delimiter, keeping all its tokens
+pub(crate) fn any_group_tokens<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Tokens, Error = E> + Clone {
+    token(None, RustToken::is_start_delim)
+        .rewind()
+        .ignore_then(token_tree())
+}
+
+/// Accepts a path separator, `::`, keeping both tokens
+pub(crate) fn path_sep_tokens<E: Error<RustToken, Span = RustSpan>>(
+) -> impl Parser<RustToken, Tokens, Error = E> + Clone {
+    joint_token(':').chain(punct_token(':'))
+}
+
+/// Accepts a return arrow, `->`, keeping both tokens
+pub(crate) fn arrow_tokens<E: Error<RustToken, Span = RustSpan>>(
+) -> impl Parser<RustToken, Tokens, Error = E> + Clone {
+    joint_token('-').chain(punct_token('>'))
+}
+
+/// Accepts one or more items separated by the punctuation `P`, allowing a trailing separator, and
+/// keeping the span of each separator
+pub(crate) fn punctuated<T, E, I, const P: char>(
+    item: I,
+) -> impl Parser<RustToken, Punctuated<T, P>, Error = E> + Clone
+where
+    E: Error<RustToken, Span = RustSpan>,
+    I: Parser<RustToken, T, Error = E> + Clone,
+{
+    item.clone()
+        .then(punct_span(P).then(item).repeated())
+        .then(punct_span(P).or_not())
+        .map(|((first, rest), trailing)| {
+            let mut list = Punctuated::new();
+            let mut prev = first;
+            for (punct, item) in rest {
+                list.push_pair(prev, Some(punct));
+                prev = item;
+            }
+            list.push_pair(prev, trailing);
+            list
+        })
+}
+
+/// Accepts zero or more items separated by the given punctuation, allowing a trailing separator,
+/// and keeping all tokens
+pub(crate) fn punctuated_tokens<E, P>(
+    item: P,
+    sep: char,
+) -> impl Parser<RustToken, Tokens, Error = E> + Clone
+where
+    E: Error<RustToken, Span = RustSpan>,
+    P: Parser<RustToken, Tokens, Error = E> + Clone,
+{
+    item.clone()
+        .chain(punct_token(sep).chain(item).repeated().flatten())
+        .chain(punct_token(sep).or_not())
+        .or_not()
+        .flatten()
+}
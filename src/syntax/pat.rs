@@ -0,0 +1,70 @@
+//! Patterns, such as `Some(ref x)` or `Foo { bar, .. } | Baz`
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+
+use super::{any_group_tokens, joint_token, path_sep_tokens, punct_token, token, verbatim_node};
+use super::{Tokens, Verbatim};
+use crate::{RustSpan, RustToken};
+
+verbatim_node! {
+    /// A pattern, such as `Some(ref x)` or `1..=5 | 10`. Patterns are kept as raw tokens.
+    Pat
+}
+
+/// Accepts a pattern, including top-level alternatives separated by `|`. The pattern ends at the
+/// first `,`, `;`, `=`, `=>`, `:`, `if` or `in` outside of any group.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::pat::pat;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = pat::<Cheap<_, RustSpan>>().then_ignore(keyword("if"));
+///
+/// let pat = parser
+///     .parse(stream_from_tokens(quote!(Some(Foo { x: 1..=5, .. }) | None if)))
+///     .unwrap();
+/// assert_eq!(pat.tokens().len(), 18);
+///
+/// parser.parse(stream_from_tokens(quote!(if))).unwrap_err();
+/// ```
+#[must_use]
+pub fn pat<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Pat, Error = E> + Clone {
+    pat_tokens(true).map(|tokens| Pat(Verbatim::new(tokens)))
+}
+
+/// Accepts a pattern, keeping its tokens. When `top_alt` is false, the pattern also ends at a
+/// `|`, as needed for closure parameters.
+pub(crate) fn pat_tokens<E: Error<RustToken, Span = RustSpan> + 'static>(
+    top_alt: bool,
+) -> impl Parser<RustToken, Tokens, Error = E> + Clone {
+    let dots_eq = joint_token('.')
+        .chain(joint_token('.'))
+        .chain(punct_token('='));
+
+    let single = token(None, move |tok| match tok {
+        RustToken::Punct(punct) => match punct.as_char() {
+            ',' | ';' | '=' | ':' => false,
+            '|' => top_alt,
+            _ => true,
+        },
+        RustToken::Ident(ident) => ident != "if" && ident != "in",
+        RustToken::Literal(_) => true,
+        RustToken::StartDelim(_) | RustToken::EndDelim(_) | RustToken::DocComment(_) => false,
+    })
+    .map(|tok| vec![tok]);
+
+    path_sep_tokens()
+        .or(dots_eq)
+        .or(any_group_tokens())
+        .or(single)
+        .repeated()
+        .at_least(1)
+        .flatten()
+        .boxed()
+}
@@ -0,0 +1,154 @@
+//! Simple paths without generic arguments, such as `std::fmt::Debug` or `serde::rename`
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use proc_macro2::{Ident, Punct, Spacing, TokenStream};
+use quote::ToTokens;
+use std::fmt;
+
+use super::joint_token;
+use crate::primitive::punct;
+use crate::{RustSpan, RustToken};
+
+/// A path without generic arguments, as used in attributes, visibilities and `use` items
+#[derive(Clone, Debug)]
+pub struct Path {
+    /// The span of the leading `::`, if there is one
+    pub leading_colon: Option<RustSpan>,
+    /// The segments of the path, in order
+    pub segments: Vec<Ident>,
+}
+
+impl Path {
+    /// Get the span covering this path
+    ///
+    /// # Panics
+    ///
+    /// If the path has no segments
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        let first = self.segments.first().expect("Path with no segments");
+        let last = self.segments.last().expect("Path with no segments");
+        self.leading_colon
+            .unwrap_or_else(|| first.span().into())
+            .join_lossy(last.span().into())
+    }
+
+    /// Get the identifier this path consists of, if it's a single identifier with no leading `::`
+    #[must_use]
+    pub fn get_ident(&self) -> Option<&Ident> {
+        match &*self.segments {
+            [ident] if self.leading_colon.is_none() => Some(ident),
+            _ => None,
+        }
+    }
+
+    /// Returns whether this path is exactly the given identifier
+    #[must_use]
+    pub fn is_ident(&self, name: &str) -> bool {
+        self.get_ident().is_some_and(|ident| ident == name)
+    }
+
+    /// Returns whether this path is spelled the same as the provided one, such as `serde::rename`
+    #[must_use]
+    pub fn is(&self, path: &str) -> bool {
+        let (leading_colon, path) = match path.strip_prefix("::") {
+            Some(path) => (true, path),
+            None => (false, path),
+        };
+        leading_colon == self.leading_colon.is_some()
+            && path.split("::").count() == self.segments.len()
+            && path
+                .split("::")
+                .zip(&self.segments)
+                .all(|(name, ident)| ident == name.trim())
+    }
+}
+
+impl PartialEq for Path {
+    fn eq(&self, other: &Self) -> bool {
+        self.leading_colon.is_some() == other.leading_colon.is_some()
+            && self.segments == other.segments
+    }
+}
+
+impl Eq for Path {}
+
+impl From<Ident> for Path {
+    fn from(ident: Ident) -> Self {
+        Path {
+            leading_colon: None,
+            segments: vec![ident],
+        }
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, ident) in self.segments.iter().enumerate() {
+            if idx > 0 || self.leading_colon.is_some() {
+                f.write_str("::")?;
+            }
+            write!(f, "{}", ident)?;
+        }
+        Ok(())
+    }
+}
+
+impl ToTokens for Path {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for (idx, ident) in self.segments.iter().enumerate() {
+            if idx > 0 || self.leading_colon.is_some() {
+                let span = match self.leading_colon {
+                    Some(span) if idx == 0 => *span,
+                    _ => ident.span(),
+                };
+                let mut first = Punct::new(':', Spacing::Joint);
+                first.set_span(span);
+                let mut second = Punct::new(':', Spacing::Alone);
+                second.set_span(span);
+                tokens.extend([first, second]);
+            }
+            ident.to_tokens(tokens);
+        }
+    }
+}
+
+/// Accepts a path without generic arguments, such as `::std::fmt::Debug`. Keywords are allowed as
+/// segments, as attributes such as `#[unsafe(no_mangle)]` may use them.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::path::path;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = path::<Cheap<_, RustSpan>>();
+///
+/// let path = parser.parse(stream_from_tokens(quote!(serde::rename))).unwrap();
+/// assert!(path.is("serde::rename"));
+/// assert_eq!(path.to_string(), "serde::rename");
+///
+/// parser.then_ignore(end()).parse(stream_from_tokens(quote!(serde::))).unwrap_err();
+/// ```
+#[must_use]
+pub fn path<E: Error<RustToken, Span = RustSpan>>(
+) -> impl Parser<RustToken, Path, Error = E> + Clone {
+    let path_sep = joint_token(':').then_ignore(punct(':'));
+
+    path_sep
+        .clone()
+        .map(|(_, span)| span)
+        .or_not()
+        .then(
+            filter_map(RustToken::filter_ident)
+                .separated_by(path_sep)
+                .at_least(1),
+        )
+        .map(|(leading_colon, segments)| Path {
+            leading_colon,
+            segments,
+        })
+}
@@ -0,0 +1,233 @@
+//! Blocks and the statements inside them, such as `{ let x = 1; foo(x); x }`
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use proc_macro2::{Delimiter, Group, Ident, TokenStream};
+use quote::{quote, ToTokens};
+
+use super::attr::{inner_attr, outer_attrs, Attribute};
+use super::expr::ExprGrammar;
+use super::item::{item, Item};
+use super::pat::pat_tokens;
+use super::ty::ty;
+use super::{group_tokens, keyword_span, punct_span, sym, token, Expr, Pat, Type, Verbatim};
+use crate::{RustSpan, RustToken};
+
+/// A braced block of statements, such as a function body
+#[derive(Clone, Debug)]
+pub struct Block {
+    /// The span of the opening `{`
+    pub open_token: RustSpan,
+    /// The inner attributes at the start of the block, such as `#![allow(unused)]`
+    pub attrs: Vec<Attribute>,
+    /// The statements in the block. A final [`Stmt::Expr`] without a `;` is the value of the
+    /// block.
+    pub stmts: Vec<Stmt>,
+    /// The span of the closing `}`
+    pub close_token: RustSpan,
+}
+
+impl Block {
+    /// Get the span covering this whole block
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        self.open_token.join_lossy(self.close_token)
+    }
+}
+
+impl ToTokens for Block {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Block { attrs, stmts, .. } = self;
+        let mut group = Group::new(Delimiter::Brace, quote!(#(#attrs)* #(#stmts)*));
+        group.set_span(*self.span());
+        group.to_tokens(tokens);
+    }
+}
+
+/// A single statement in a block
+#[derive(Clone, Debug)]
+pub enum Stmt {
+    /// A let statement, such as `let Some(x) = y else { return };`
+    Local(Local),
+    /// An item, such as `fn inner() {}`
+    Item(Box<Item>),
+    /// An expression statement, such as `foo();` or `if x {}`, or the trailing expression of the
+    /// block
+    Expr {
+        /// The attributes on the statement
+        attrs: Vec<Attribute>,
+        /// The expression
+        expr: Expr,
+        /// The span of the `;`, if present. Block-like expressions, such as `if x {}`, and the
+        /// trailing expression don't need one.
+        semi_token: Option<RustSpan>,
+    },
+    /// An empty statement, `;`, with its span
+    Empty(RustSpan),
+}
+
+impl ToTokens for Stmt {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Stmt::Local(local) => local.to_tokens(tokens),
+            Stmt::Item(item) => item.to_tokens(tokens),
+            Stmt::Expr {
+                attrs,
+                expr,
+                semi_token,
+            } => {
+                let semi_token = semi_token.map(|span| sym(';', span));
+                tokens.extend(quote!(#(#attrs)* #expr #semi_token));
+            }
+            Stmt::Empty(span) => sym(';', *span).to_tokens(tokens),
+        }
+    }
+}
+
+/// A let statement, such as `let x: u8 = 1;` or `let Some(x) = y else { return };`
+#[derive(Clone, Debug)]
+pub struct Local {
+    /// The attributes on the statement
+    pub attrs: Vec<Attribute>,
+    /// The span of the `let` keyword
+    pub let_token: RustSpan,
+    /// The pattern being bound
+    pub pat: Pat,
+    /// The type annotation, with the span of the `:`, if present
+    pub ty: Option<(RustSpan, Type)>,
+    /// The initializer, with the span of the `=`, if present
+    pub init: Option<(RustSpan, Expr)>,
+    /// The `else` block of a let-else statement, with the span of the `else` keyword
+    pub diverge: Option<(RustSpan, Verbatim)>,
+    /// The span of the `;`
+    pub semi_token: RustSpan,
+}
+
+impl ToTokens for Local {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Local {
+            attrs,
+            let_token,
+            pat,
+            ty,
+            init,
+            diverge,
+            semi_token,
+        } = self;
+        let let_token = Ident::new("let", **let_token);
+        tokens.extend(quote!(#(#attrs)* #let_token #pat));
+        if let Some((colon_token, ty)) = ty {
+            let colon_token = sym(':', *colon_token);
+            tokens.extend(quote!(#colon_token #ty));
+        }
+        if let Some((eq_token, init)) = init {
+            let eq_token = sym('=', *eq_token);
+            tokens.extend(quote!(#eq_token #init));
+        }
+        if let Some((else_token, block)) = diverge {
+            let else_token = Ident::new("else", **else_token);
+            tokens.extend(quote!(#else_token #block));
+        }
+        sym(';', *semi_token).to_tokens(tokens);
+    }
+}
+
+/// Accepts a braced block, splitting it into statements. A block-like expression such as
+/// `if x {}` ends its statement, so `if x {} foo()` is two statements.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::stmt::{block, Stmt};
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::{quote, ToTokens};
+/// let parser = block::<Cheap<_, RustSpan>>().then_ignore(end());
+///
+/// let input = quote!({
+///     let Some(x): Option<u8> = y else { return };
+///     if x {} foo();;
+///     fn inner() {}
+///     match x { _ => () } - 1;
+///     x + 1
+/// });
+/// let block = parser.parse(stream_from_tokens(input.clone())).unwrap();
+/// assert_eq!(block.stmts.len(), 8);
+/// assert!(matches!(block.stmts[3], Stmt::Empty(_)));
+/// assert!(matches!(block.stmts[7], Stmt::Expr { semi_token: None, .. }));
+/// assert_eq!(block.to_token_stream().to_string(), input.to_string());
+///
+/// parser.parse(stream_from_tokens(quote!({ foo() bar() }))).unwrap_err();
+/// ```
+#[must_use]
+pub fn block<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Block, Error = E> + Clone {
+    let exprs = ExprGrammar::new();
+    let expr = |tokens| Expr(Verbatim::new(tokens));
+
+    // Block-like statements may be followed by a `.` or `?`, continuing the expression
+    let ends_block_like = token(None, |tok| {
+        tok.as_punct()
+            .is_none_or(|p| p.as_char() != '.' && p.as_char() != '?')
+    })
+    .rewind();
+    let ends_block = just(RustToken::EndDelim(Delimiter::Brace)).rewind();
+
+    let local = outer_attrs()
+        .then(keyword_span("let"))
+        .then(pat_tokens(true).map(|tokens| Pat(Verbatim::new(tokens))))
+        .then(punct_span(':').then(ty()).or_not())
+        .then(punct_span('=').then(exprs.expr.clone().map(expr)).or_not())
+        .then(
+            keyword_span("else")
+                .then(group_tokens(Delimiter::Brace).map(Verbatim::new))
+                .or_not(),
+        )
+        .then(punct_span(';'))
+        .map(
+            |((((((attrs, let_token), pat), ty), init), diverge), semi_token)| {
+                Stmt::Local(Local {
+                    attrs,
+                    let_token,
+                    pat,
+                    ty,
+                    init,
+                    diverge,
+                    semi_token,
+                })
+            },
+        );
+
+    let block_like = outer_attrs()
+        .then(exprs.block_like.or(exprs.brace_mac).map(expr))
+        .then(punct_span(';').map(Some).or(ends_block_like.to(None)));
+
+    let expr_stmt = outer_attrs()
+        .then(exprs.expr.map(expr))
+        .then(punct_span(';').map(Some).or(ends_block.to(None)));
+
+    let stmt = local
+        .or(block_like
+            .or(expr_stmt)
+            .map(|((attrs, expr), semi_token)| Stmt::Expr {
+                attrs,
+                expr,
+                semi_token,
+            }))
+        .or(item().map(|item| Stmt::Item(Box::new(item))))
+        .or(punct_span(';').map(Stmt::Empty));
+
+    just(RustToken::StartDelim(Delimiter::Brace))
+        .map_with_span(|_, span| span)
+        .then(inner_attr().repeated())
+        .then(stmt.repeated())
+        .then(just(RustToken::EndDelim(Delimiter::Brace)).map_with_span(|_, span| span))
+        .map(|(((open_token, attrs), stmts), close_token)| Block {
+            open_token,
+            attrs,
+            stmts,
+            close_token,
+        })
+        .boxed()
+}
@@ -0,0 +1,306 @@
+//! Types and trait bounds, such as `Vec<&'a str>` or `Iterator<Item = u8> + 'a`
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use proc_macro2::{Delimiter, TokenStream};
+use quote::ToTokens;
+
+use super::{
+    any_group_tokens, arrow_tokens, group_tokens, keyword_token, lifetime, lifetime_tokens,
+    literal_token, path_sep_tokens, punct_token, punctuated, punctuated_tokens, token,
+    verbatim_node, Lifetime, Punctuated, Tokens, Verbatim,
+};
+use crate::utils::is_path_segment;
+use crate::{RustSpan, RustToken};
+
+verbatim_node! {
+    /// A Rust type, such as `Vec<&'a str>` or `impl Fn() -> u8`. Types are validated while
+    /// parsing, but kept as raw tokens.
+    Type
+}
+
+/// A single bound on a type, as found after the `:` of a type parameter or where predicate
+#[derive(Clone, Debug)]
+pub enum TypeBound {
+    /// A lifetime bound, such as `'a`
+    Lifetime(Lifetime),
+    /// A trait bound, such as `?Sized` or `for<'a> Fn(&'a str)`, kept as raw tokens
+    Trait(Verbatim),
+}
+
+impl TypeBound {
+    /// Get the span covering this bound
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        match self {
+            TypeBound::Lifetime(lt) => lt.span(),
+            TypeBound::Trait(tokens) => tokens.span(),
+        }
+    }
+
+    /// Get the flat tokens making up this bound
+    pub(crate) fn tokens(&self) -> Tokens {
+        match self {
+            TypeBound::Lifetime(lt) => lt.tokens(),
+            TypeBound::Trait(tokens) => tokens.tokens().to_vec(),
+        }
+    }
+}
+
+impl ToTokens for TypeBound {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            TypeBound::Lifetime(lt) => lt.to_tokens(tokens),
+            TypeBound::Trait(bound) => bound.to_tokens(tokens),
+        }
+    }
+}
+
+/// Accepts a Rust type
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::ty::ty;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = ty::<Cheap<_, RustSpan>>().then_ignore(end());
+///
+/// parser.parse(stream_from_tokens(quote!(Vec<&'a str>))).unwrap();
+/// parser.parse(stream_from_tokens(quote!(<T as Iterator>::Item))).unwrap();
+/// parser.parse(stream_from_tokens(quote!(Box<dyn Fn(u8) -> u8 + Send>))).unwrap();
+///
+/// parser.parse(stream_from_tokens(quote!(Vec<u8))).unwrap_err();
+/// ```
+#[must_use]
+pub fn ty<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Type, Error = E> + Clone {
+    TypeGrammar::new()
+        .ty
+        .map(|tokens| Type(Verbatim::new(tokens)))
+}
+
+/// Accepts one or more trait or lifetime bounds separated by `+`, such as `Clone + 'a`. A
+/// trailing `+` is allowed.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::ty::type_bounds;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use quote::quote;
+/// let parser = type_bounds::<Cheap<_, RustSpan>>().then_ignore(end());
+///
+/// let bounds = parser
+///     .parse(stream_from_tokens(quote!(?Sized + for<'a> Fn(&'a str) + 'static)))
+///     .unwrap();
+/// assert_eq!(bounds.len(), 3);
+/// ```
+#[must_use]
+pub fn type_bounds<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Punctuated<TypeBound, '+'>, Error = E> + Clone {
+    TypeGrammar::new().bounds
+}
+
+/// The mutually recursive parsers making up the type grammar. Types contain paths, paths contain
+/// generic arguments, and generic arguments contain types and bounds, so they're all built
+/// together.
+pub(crate) struct TypeGrammar<E: Error<RustToken, Span = RustSpan> + 'static> {
+    /// Accepts a type, keeping its tokens
+    pub ty: BoxedParser<'static, RustToken, Tokens, E>,
+    /// Accepts a path in type position, such as `::std::vec::Vec<T>`, keeping its tokens
+    pub path: BoxedParser<'static, RustToken, Tokens, E>,
+    /// Accepts bounds separated by `+`, allowing a trailing `+`
+    pub bounds: BoxedParser<'static, RustToken, Punctuated<TypeBound, '+'>, E>,
+    /// Accepts angle-bracketed generic arguments, such as `<'a, T, Item = u8, 3>`, keeping their
+    /// tokens
+    pub generic_args: BoxedParser<'static, RustToken, Tokens, E>,
+}
+
+impl<E: Error<RustToken, Span = RustSpan> + 'static> TypeGrammar<E> {
+    /// Build the type grammar
+    pub fn new() -> TypeGrammar<E> {
+        let mut ty = Recursive::declare();
+        let mut generic_args = Recursive::declare();
+
+        let return_type = arrow_tokens().chain(ty.clone()).or_not().flatten();
+
+        let segment = segment_ident_token()
+            .chain(
+                path_sep_tokens()
+                    .or_not()
+                    .flatten()
+                    .chain(generic_args.clone())
+                    .or(group_tokens(Delimiter::Parenthesis).chain(return_type.clone()))
+                    .or_not()
+                    .flatten(),
+            )
+            .boxed();
+
+        let path = path_sep_tokens()
+            .or_not()
+            .flatten()
+            .chain(segment.clone())
+            .chain(
+                path_sep_tokens()
+                    .chain(segment.clone())
+                    .repeated()
+                    .flatten(),
+            )
+            .boxed();
+
+        let trait_bound = punct_token('?')
+            .or_not()
+            .chain(
+                punct_token('~')
+                    .chain(keyword_token("const"))
+                    .or_not()
+                    .flatten(),
+            )
+            .chain(for_lifetimes().or_not().flatten())
+            .chain(path.clone());
+
+        let precise_capture = keyword_token("use").chain(lifetime_list());
+
+        let bound = lifetime()
+            .map(TypeBound::Lifetime)
+            .or(group_tokens(Delimiter::Parenthesis)
+                .or(precise_capture)
+                .or(trait_bound)
+                .map(|tokens| TypeBound::Trait(Verbatim::new(tokens))))
+            .boxed();
+
+        let bounds = punctuated(bound.clone()).boxed();
+
+        let bound_tokens = bound.map(|bound| bound.tokens());
+        let bounds_tokens = bound_tokens
+            .clone()
+            .chain(punct_token('+').chain(bound_tokens).repeated().flatten())
+            .boxed();
+
+        let name = segment_ident_token().chain(generic_args.clone().or_not().flatten());
+        let arg = choice((
+            lifetime_tokens(),
+            name.clone()
+                .chain(punct_token('='))
+                .chain(ty.clone())
+                .boxed(),
+            name.chain(punct_token(':'))
+                .chain(bounds_tokens.clone())
+                .boxed(),
+            ty.clone().boxed(),
+            const_arg_tokens().boxed(),
+        ));
+
+        generic_args.define(
+            punct_token('<')
+                .chain(punctuated_tokens(arg, ','))
+                .chain(punct_token('>')),
+        );
+
+        let reference = punct_token('&')
+            .chain(lifetime_tokens().or_not().flatten())
+            .chain(keyword_token("mut").or_not())
+            .chain(ty.clone());
+
+        let pointer = punct_token('*')
+            .chain(keyword_token("const").or(keyword_token("mut")))
+            .chain(ty.clone());
+
+        let fn_ptr = keyword_token("unsafe")
+            .or_not()
+            .chain(
+                keyword_token("extern")
+                    .chain(literal_token().or_not())
+                    .or_not()
+                    .flatten(),
+            )
+            .chain(keyword_token("fn"))
+            .chain(group_tokens(Delimiter::Parenthesis))
+            .chain(return_type);
+
+        let qualified = punct_token('<')
+            .chain(ty.clone())
+            .chain(keyword_token("as").chain(path.clone()).or_not().flatten())
+            .chain(punct_token('>'))
+            .chain(
+                path_sep_tokens()
+                    .chain(segment)
+                    .repeated()
+                    .at_least(1)
+                    .flatten(),
+            );
+
+        let mac = punct_token('!').chain(any_group_tokens());
+
+        ty.define(choice((
+            group_tokens(Delimiter::Parenthesis),
+            group_tokens(Delimiter::Bracket),
+            punct_token('!').map(|tok| vec![tok]),
+            keyword_token("_").map(|tok| vec![tok]),
+            reference.boxed(),
+            pointer.boxed(),
+            for_lifetimes()
+                .or_not()
+                .flatten()
+                .chain(fn_ptr.or(path.clone().chain(mac.or_not().flatten())))
+                .boxed(),
+            keyword_token("impl")
+                .or(keyword_token("dyn"))
+                .chain(bounds_tokens)
+                .boxed(),
+            qualified.boxed(),
+        )));
+
+        TypeGrammar {
+            ty: ty.boxed(),
+            path,
+            bounds,
+            generic_args: generic_args.boxed(),
+        }
+    }
+}
+
+/// Accepts an identifier usable as a path segment, which includes `self`, `Self`, `super` and
+/// `crate`
+pub(crate) fn segment_ident_token<E: Error<RustToken, Span = RustSpan>>(
+) -> impl Parser<RustToken, (RustToken, RustSpan), Error = E> + Clone {
+    token(None, |tok| {
+        tok.as_ident()
+            .is_some_and(|ident| is_path_segment(&ident.to_string()))
+    })
+}
+
+/// Accepts a const generic argument that isn't a plain path: a literal, a negated literal, or a
+/// block
+pub(crate) fn const_arg_tokens<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Tokens, Error = E> + Clone {
+    punct_token('-')
+        .or_not()
+        .chain(literal_token())
+        .or(group_tokens(Delimiter::Brace))
+}
+
+/// Accepts a higher-ranked lifetime binder, such as `for<'a, 'b: 'a>`, keeping its tokens
+pub(crate) fn for_lifetimes<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Tokens, Error = E> + Clone {
+    keyword_token("for").chain(lifetime_list())
+}
+
+/// Accepts an angle-bracketed list of lifetimes, as found in `for<'a>` or `use<'a>`, keeping its
+/// tokens
+fn lifetime_list<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Tokens, Error = E> + Clone {
+    punct_token('<')
+        .chain(
+            token(None, |tok| {
+                !tok.is_delim() && tok.as_punct().is_none_or(|p| p.as_char() != '>')
+            })
+            .repeated(),
+        )
+        .chain(punct_token('>'))
+}
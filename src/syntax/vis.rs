@@ -0,0 +1,147 @@
+//! Visibilities, such as `pub` or `pub(crate)`
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use proc_macro2::{Delimiter, Group, Ident, TokenStream};
+use quote::ToTokens;
+
+use super::keyword_span;
+use super::path::{path, Path};
+use crate::{RustSpan, RustToken};
+
+/// The visibility of an item or field
+#[derive(Clone, Debug, Default)]
+pub enum Visibility {
+    /// No visibility was written, so the item is private
+    #[default]
+    Inherited,
+    /// Visible everywhere, `pub`
+    Public {
+        /// The span of the `pub` keyword
+        pub_token: RustSpan,
+    },
+    /// Visible within a path, such as `pub(crate)` or `pub(in some::path)`
+    Restricted {
+        /// The span of the `pub` keyword
+        pub_token: RustSpan,
+        /// The span of the opening parenthesis
+        open_token: RustSpan,
+        /// The span of the `in` keyword, if there is one
+        in_token: Option<RustSpan>,
+        /// The path the item is visible within, such as `crate` or `super`
+        path: Path,
+        /// The span of the closing parenthesis
+        close_token: RustSpan,
+    },
+}
+
+impl Visibility {
+    /// Returns whether no visibility was written
+    #[must_use]
+    pub fn is_inherited(&self) -> bool {
+        matches!(self, Visibility::Inherited)
+    }
+
+    /// Get the span covering this visibility, or `None` if it was inherited
+    #[must_use]
+    pub fn span(&self) -> Option<RustSpan> {
+        match self {
+            Visibility::Inherited => None,
+            Visibility::Public { pub_token } => Some(*pub_token),
+            Visibility::Restricted {
+                pub_token,
+                close_token,
+                ..
+            } => Some(pub_token.join_lossy(*close_token)),
+        }
+    }
+}
+
+impl ToTokens for Visibility {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Visibility::Inherited => (),
+            Visibility::Public { pub_token } => {
+                Ident::new("pub", **pub_token).to_tokens(tokens);
+            }
+            Visibility::Restricted {
+                pub_token,
+                open_token,
+                in_token,
+                path,
+                close_token,
+            } => {
+                Ident::new("pub", **pub_token).to_tokens(tokens);
+                let mut inner = TokenStream::new();
+                if let Some(in_token) = in_token {
+                    Ident::new("in", **in_token).to_tokens(&mut inner);
+                }
+                path.to_tokens(&mut inner);
+                let mut group = Group::new(Delimiter::Parenthesis, inner);
+                group.set_span(*open_token.join_lossy(*close_token));
+                group.to_tokens(tokens);
+            }
+        }
+    }
+}
+
+/// Accepts a visibility, which may be empty
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::syntax::vis::visibility;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use proc_macro2::Delimiter;
+/// # use quote::{quote, ToTokens};
+/// let parser = visibility::<Cheap<_, RustSpan>>().then_ignore(keyword("fn"));
+///
+/// let vis = parser.parse(stream_from_tokens(quote!(fn))).unwrap();
+/// assert!(vis.is_inherited());
+///
+/// let vis = parser.parse(stream_from_tokens(quote!(pub(in crate::a) fn))).unwrap();
+/// assert_eq!(vis.to_token_stream().to_string(), quote!(pub(in crate::a)).to_string());
+///
+/// // A parenthesized type after `pub` isn't part of the visibility
+/// visibility::<Cheap<_, RustSpan>>()
+///     .then_ignore(group(Delimiter::Parenthesis, empty()))
+///     .parse(stream_from_tokens(quote!(pub ())))
+///     .unwrap();
+///
+/// parser.parse(stream_from_tokens(quote!(pub(a) fn))).unwrap_err();
+/// ```
+#[must_use]
+pub fn visibility<E: Error<RustToken, Span = RustSpan> + 'static>(
+) -> impl Parser<RustToken, Visibility, Error = E> + Clone {
+    let restricted =
+        just(RustToken::StartDelim(Delimiter::Parenthesis))
+            .map_with_span(|_, span| span)
+            .then(keyword_span("in").map(Some).then(path()).or(filter_map(
+                |span, tok: RustToken| match tok {
+                    RustToken::Ident(ident)
+                        if ident == "crate" || ident == "super" || ident == "self" =>
+                    {
+                        Ok((None, Path::from(ident)))
+                    }
+                    tok => Err(E::expected_input_found(span, [], Some(tok))),
+                },
+            )))
+            .then(just(RustToken::EndDelim(Delimiter::Parenthesis)).map_with_span(|_, span| span));
+
+    keyword_span("pub")
+        .then(restricted.or_not())
+        .map(|(pub_token, restricted)| match restricted {
+            None => Visibility::Public { pub_token },
+            Some(((open_token, (in_token, path)), close_token)) => Visibility::Restricted {
+                pub_token,
+                open_token,
+                in_token,
+                path,
+                close_token,
+            },
+        })
+        .or_not()
+        .map(Option::unwrap_or_default)
+}
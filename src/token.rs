@@ -4,6 +4,7 @@
 use proc_macro2::{Delimiter, Ident, Literal, Punct, Spacing};
 use std::hash::{Hash, Hasher};
 
+use crate::syntax::doc::DocComment;
 use crate::utils::{lit_eq, punct_eq};
 use crate::RustSpan;
 
@@ -42,7 +43,10 @@ macro_rules! impl_items {
 
 /// A Rust Token - The flattened form of a [`TokenTree`][proc_macro2::TokenTree] with groups
 /// converted into start and end delimiters.
+///
+/// New kinds of token may be added in minor releases, so matches on this need a wildcard arm.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum RustToken {
     /// A literal - Something like `1` or `"foo"`
     Literal(Literal),
@@ -56,6 +60,10 @@ pub enum RustToken {
     /// An end delimiter for a group. All end delimiters have matching start delimiters due to
     /// Rust's macro parsing rules.
     EndDelim(Delimiter),
+    /// A whole doc comment, such as `/// Some docs`. These are only produced by
+    /// [`stream_with_docs`][crate::syntax::doc::stream_with_docs] - otherwise, doc comments
+    /// appear as their `#[doc = "..."]` attribute tokens.
+    DocComment(DocComment),
 }
 
 impl RustToken {
@@ -122,6 +130,14 @@ impl RustToken {
         as_end_delim,
         into_end_delim
     );
+    impl_items!(
+        DocComment,
+        DocComment,
+        "a doc comment",
+        is_doc_comment,
+        as_doc_comment,
+        into_doc_comment
+    );
 
     /// Returns whether this token is a delimiter - start or end
     #[must_use]
@@ -149,6 +165,9 @@ impl PartialEq for RustToken {
             (RustToken::Punct(this), RustToken::Punct(other)) => punct_eq(this, other),
             (RustToken::StartDelim(this), RustToken::StartDelim(other))
             | (RustToken::EndDelim(this), RustToken::EndDelim(other)) => this == other,
+            (RustToken::DocComment(this), RustToken::DocComment(other)) => {
+                this.style == other.style && this.text == other.text
+            }
             _ => false,
         }
     }
@@ -156,21 +175,19 @@ impl PartialEq for RustToken {
 
 impl PartialEq<Literal> for RustToken {
     fn eq(&self, other: &Literal) -> bool {
-        self.as_literal()
-            .map_or(false, |lit| lit.to_string() == other.to_string())
+        self.as_literal().is_some_and(|lit| lit_eq(lit, other))
     }
 }
 
 impl PartialEq<Ident> for RustToken {
     fn eq(&self, other: &Ident) -> bool {
-        self.as_ident().map_or(false, |ident| ident == other)
+        self.as_ident() == Some(other)
     }
 }
 
 impl PartialEq<Punct> for RustToken {
     fn eq(&self, other: &Punct) -> bool {
-        self.as_punct()
-            .map_or(false, |punct| punct_eq(punct, other))
+        self.as_punct().is_some_and(|punct| punct_eq(punct, other))
     }
 }
 
@@ -213,6 +230,10 @@ impl Hash for RustToken {
                     Delimiter::None => state.write_u8(3),
                 }
             }
+            RustToken::DocComment(doc) => {
+                state.write_u8(5);
+                doc.text.hash(state);
+            }
         }
     }
 }
@@ -0,0 +1,376 @@
+//! Typed keywords and punctuation, such as `fn` or `+=`, which remember their span
+//!
+//! These are usually named with the [`Token!`][crate::Token] macro, so `Token![fn]` is
+//! [`Fn`] and `Token![+=]` is [`PlusEq`]. Each has a `parser()` built on
+//! [`keyword`], [`punct`] or [`joined_punct`], and re-emits itself at its original span through
+//! `ToTokens`, so structural tokens can be kept in an AST and re-emitted exactly.
+
+use chumsky::error::Error;
+use chumsky::prelude::*;
+use proc_macro2::{Ident, Punct, Spacing, Span, TokenStream};
+use quote::ToTokens;
+
+use crate::parse::Parse;
+use crate::primitive::{joined_punct, keyword, punct};
+use crate::{RustSpan, RustToken};
+
+/// Define a struct for each keyword, along with its parser and `ToTokens` implementation
+macro_rules! define_keywords {
+    ($($token:literal pub struct $name:ident)*) => {$(
+        #[doc = concat!("The `", $token, "` keyword")]
+        #[derive(Copy, Clone, Debug)]
+        pub struct $name {
+            /// The span of the keyword
+            pub span: RustSpan,
+        }
+
+        impl $name {
+            #[doc = concat!("Create a `", $token, "` keyword with the given span")]
+            #[must_use]
+            pub fn new(span: RustSpan) -> $name {
+                $name { span }
+            }
+
+            #[doc = concat!("Accepts the `", $token, "` keyword, keeping its span")]
+            #[must_use]
+            pub fn parser<E: Error<RustToken, Span = RustSpan> + 'static>(
+            ) -> impl Parser<RustToken, $name, Error = E> + Clone {
+                keyword($token).map_with_span(|(), span| $name { span })
+            }
+        }
+
+        impl Parse for $name {
+            fn parser<E: Error<RustToken, Span = RustSpan> + 'static>(
+            ) -> impl Parser<RustToken, Self, Error = E> + Clone {
+                $name::parser()
+            }
+        }
+
+        impl ::core::default::Default for $name {
+            fn default() -> Self {
+                $name::new(Span::call_site().into())
+            }
+        }
+
+        impl ToTokens for $name {
+            fn to_tokens(&self, tokens: &mut TokenStream) {
+                Ident::new($token, *self.span).to_tokens(tokens);
+            }
+        }
+    )*};
+}
+
+/// Define a struct for each piece of punctuation, along with its parser and `ToTokens`
+/// implementation
+macro_rules! define_puncts {
+    ($($token:literal pub struct $name:ident)*) => {$(
+        #[doc = concat!("The `", $token, "` punctuation")]
+        #[derive(Copy, Clone, Debug)]
+        pub struct $name {
+            /// The span of the punctuation. When it's made of multiple characters, this is the
+            /// span of them all where spans can be joined, or else of the first one.
+            pub span: RustSpan,
+        }
+
+        impl $name {
+            #[doc = concat!("Create `", $token, "` punctuation with the given span")]
+            #[must_use]
+            pub fn new(span: RustSpan) -> $name {
+                $name { span }
+            }
+
+            #[doc = concat!("Accepts `", $token, "`, keeping its span")]
+            #[must_use]
+            pub fn parser<E: Error<RustToken, Span = RustSpan> + 'static>(
+            ) -> impl Parser<RustToken, $name, Error = E> + Clone {
+                punct_parser($token).map_with_span(|(), span| $name { span })
+            }
+        }
+
+        impl Parse for $name {
+            fn parser<E: Error<RustToken, Span = RustSpan> + 'static>(
+            ) -> impl Parser<RustToken, Self, Error = E> + Clone {
+                $name::parser()
+            }
+        }
+
+        impl ::core::default::Default for $name {
+            fn default() -> Self {
+                $name::new(Span::call_site().into())
+            }
+        }
+
+        impl ToTokens for $name {
+            fn to_tokens(&self, tokens: &mut TokenStream) {
+                punct_to_tokens($token, self.span, tokens);
+            }
+        }
+    )*};
+}
+
+/// Accepts a single punctuation character, or several joined together
+fn punct_parser<E: Error<RustToken, Span = RustSpan> + 'static>(
+    token: &'static str,
+) -> impl Parser<RustToken, (), Error = E> + Clone {
+    let mut chars = token.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => punct(c).boxed(),
+        _ => joined_punct(token).ignored().boxed(),
+    }
+}
+
+/// Emit punctuation, joining every character to the next
+fn punct_to_tokens(token: &str, span: RustSpan, tokens: &mut TokenStream) {
+    let count = token.chars().count();
+    for (idx, c) in token.chars().enumerate() {
+        let spacing = if idx + 1 < count {
+            Spacing::Joint
+        } else {
+            Spacing::Alone
+        };
+        let mut punct = Punct::new(c, spacing);
+        punct.set_span(*span);
+        punct.to_tokens(tokens);
+    }
+}
+
+define_keywords! {
+    "abstract" pub struct Abstract
+    "as" pub struct As
+    "async" pub struct Async
+    "auto" pub struct Auto
+    "await" pub struct Await
+    "become" pub struct Become
+    "box" pub struct Box
+    "break" pub struct Break
+    "const" pub struct Const
+    "continue" pub struct Continue
+    "crate" pub struct Crate
+    "default" pub struct Default
+    "do" pub struct Do
+    "dyn" pub struct Dyn
+    "else" pub struct Else
+    "enum" pub struct Enum
+    "extern" pub struct Extern
+    "final" pub struct Final
+    "fn" pub struct Fn
+    "for" pub struct For
+    "if" pub struct If
+    "impl" pub struct Impl
+    "in" pub struct In
+    "let" pub struct Let
+    "loop" pub struct Loop
+    "macro" pub struct Macro
+    "match" pub struct Match
+    "mod" pub struct Mod
+    "move" pub struct Move
+    "mut" pub struct Mut
+    "override" pub struct Override
+    "priv" pub struct Priv
+    "pub" pub struct Pub
+    "raw" pub struct Raw
+    "ref" pub struct Ref
+    "return" pub struct Return
+    "Self" pub struct SelfType
+    "self" pub struct SelfValue
+    "static" pub struct Static
+    "struct" pub struct Struct
+    "super" pub struct Super
+    "trait" pub struct Trait
+    "try" pub struct Try
+    "type" pub struct Type
+    "typeof" pub struct Typeof
+    "union" pub struct Union
+    "unsafe" pub struct Unsafe
+    "unsized" pub struct Unsized
+    "use" pub struct Use
+    "virtual" pub struct Virtual
+    "where" pub struct Where
+    "while" pub struct While
+    "yield" pub struct Yield
+    "_" pub struct Underscore
+}
+
+define_puncts! {
+    "&" pub struct And
+    "&&" pub struct AndAnd
+    "&=" pub struct AndEq
+    "@" pub struct At
+    "^" pub struct Caret
+    "^=" pub struct CaretEq
+    ":" pub struct Colon
+    "," pub struct Comma
+    "$" pub struct Dollar
+    "." pub struct Dot
+    ".." pub struct DotDot
+    "..." pub struct DotDotDot
+    "..=" pub struct DotDotEq
+    "=" pub struct Eq
+    "==" pub struct EqEq
+    "=>" pub struct FatArrow
+    ">=" pub struct Ge
+    ">" pub struct Gt
+    "<-" pub struct LArrow
+    "<=" pub struct Le
+    "<" pub struct Lt
+    "-" pub struct Minus
+    "-=" pub struct MinusEq
+    "!=" pub struct Ne
+    "!" pub struct Not
+    "|" pub struct Or
+    "|=" pub struct OrEq
+    "||" pub struct OrOr
+    "::" pub struct PathSep
+    "%" pub struct Percent
+    "%=" pub struct PercentEq
+    "+" pub struct Plus
+    "+=" pub struct PlusEq
+    "#" pub struct Pound
+    "?" pub struct Question
+    "->" pub struct RArrow
+    ";" pub struct Semi
+    "<<" pub struct Shl
+    "<<=" pub struct ShlEq
+    ">>" pub struct Shr
+    ">>=" pub struct ShrEq
+    "/" pub struct Slash
+    "/=" pub struct SlashEq
+    "*" pub struct Star
+    "*=" pub struct StarEq
+    "~" pub struct Tilde
+}
+
+/// Name the typed token for a keyword or piece of punctuation, such as `Token![fn]` or
+/// `Token![+=]`. Use `<Token![fn]>::parser()` to accept it.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::Token;
+/// # use chumsky::prelude::*;
+/// # use chumsky::error::Cheap;
+/// # use proc_macro2::Ident;
+/// # use quote::{quote, ToTokens};
+/// #[derive(Debug)]
+/// struct Assign {
+///     name: Ident,
+///     op: Token![+=],
+///     semi: Token![;],
+/// }
+///
+/// let parser = ident::<Cheap<_, RustSpan>>()
+///     .then(<Token![+=]>::parser())
+///     .then_ignore(ident())
+///     .then(<Token![;]>::parser())
+///     .map(|((name, op), semi)| Assign { name, op, semi });
+///
+/// let assign = parser.parse(stream_from_tokens(quote!(a += b;))).unwrap();
+/// assert_eq!(assign.name, "a");
+/// assert_eq!(assign.op.to_token_stream().to_string(), "+=");
+///
+/// parser.parse(stream_from_tokens(quote!(a + = b;))).unwrap_err();
+/// ```
+#[macro_export]
+macro_rules! Token {
+    [abstract] => { $crate::tokens::Abstract };
+    [as] => { $crate::tokens::As };
+    [async] => { $crate::tokens::Async };
+    [auto] => { $crate::tokens::Auto };
+    [await] => { $crate::tokens::Await };
+    [become] => { $crate::tokens::Become };
+    [box] => { $crate::tokens::Box };
+    [break] => { $crate::tokens::Break };
+    [const] => { $crate::tokens::Const };
+    [continue] => { $crate::tokens::Continue };
+    [crate] => { $crate::tokens::Crate };
+    [default] => { $crate::tokens::Default };
+    [do] => { $crate::tokens::Do };
+    [dyn] => { $crate::tokens::Dyn };
+    [else] => { $crate::tokens::Else };
+    [enum] => { $crate::tokens::Enum };
+    [extern] => { $crate::tokens::Extern };
+    [final] => { $crate::tokens::Final };
+    [fn] => { $crate::tokens::Fn };
+    [for] => { $crate::tokens::For };
+    [if] => { $crate::tokens::If };
+    [impl] => { $crate::tokens::Impl };
+    [in] => { $crate::tokens::In };
+    [let] => { $crate::tokens::Let };
+    [loop] => { $crate::tokens::Loop };
+    [macro] => { $crate::tokens::Macro };
+    [match] => { $crate::tokens::Match };
+    [mod] => { $crate::tokens::Mod };
+    [move] => { $crate::tokens::Move };
+    [mut] => { $crate::tokens::Mut };
+    [override] => { $crate::tokens::Override };
+    [priv] => { $crate::tokens::Priv };
+    [pub] => { $crate::tokens::Pub };
+    [raw] => { $crate::tokens::Raw };
+    [ref] => { $crate::tokens::Ref };
+    [return] => { $crate::tokens::Return };
+    [Self] => { $crate::tokens::SelfType };
+    [self] => { $crate::tokens::SelfValue };
+    [static] => { $crate::tokens::Static };
+    [struct] => { $crate::tokens::Struct };
+    [super] => { $crate::tokens::Super };
+    [trait] => { $crate::tokens::Trait };
+    [try] => { $crate::tokens::Try };
+    [type] => { $crate::tokens::Type };
+    [typeof] => { $crate::tokens::Typeof };
+    [union] => { $crate::tokens::Union };
+    [unsafe] => { $crate::tokens::Unsafe };
+    [unsized] => { $crate::tokens::Unsized };
+    [use] => { $crate::tokens::Use };
+    [virtual] => { $crate::tokens::Virtual };
+    [where] => { $crate::tokens::Where };
+    [while] => { $crate::tokens::While };
+    [yield] => { $crate::tokens::Yield };
+    [_] => { $crate::tokens::Underscore };
+    [&] => { $crate::tokens::And };
+    [&&] => { $crate::tokens::AndAnd };
+    [&=] => { $crate::tokens::AndEq };
+    [@] => { $crate::tokens::At };
+    [^] => { $crate::tokens::Caret };
+    [^=] => { $crate::tokens::CaretEq };
+    [:] => { $crate::tokens::Colon };
+    [,] => { $crate::tokens::Comma };
+    [$] => { $crate::tokens::Dollar };
+    [.] => { $crate::tokens::Dot };
+    [..] => { $crate::tokens::DotDot };
+    [...] => { $crate::tokens::DotDotDot };
+    [..=] => { $crate::tokens::DotDotEq };
+    [=] => { $crate::tokens::Eq };
+    [==] => { $crate::tokens::EqEq };
+    [=>] => { $crate::tokens::FatArrow };
+    [>=] => { $crate::tokens::Ge };
+    [>] => { $crate::tokens::Gt };
+    [<-] => { $crate::tokens::LArrow };
+    [<=] => { $crate::tokens::Le };
+    [<] => { $crate::tokens::Lt };
+    [-] => { $crate::tokens::Minus };
+    [-=] => { $crate::tokens::MinusEq };
+    [!=] => { $crate::tokens::Ne };
+    [!] => { $crate::tokens::Not };
+    [|] => { $crate::tokens::Or };
+    [|=] => { $crate::tokens::OrEq };
+    [||] => { $crate::tokens::OrOr };
+    [::] => { $crate::tokens::PathSep };
+    [%] => { $crate::tokens::Percent };
+    [%=] => { $crate::tokens::PercentEq };
+    [+] => { $crate::tokens::Plus };
+    [+=] => { $crate::tokens::PlusEq };
+    [#] => { $crate::tokens::Pound };
+    [?] => { $crate::tokens::Question };
+    [->] => { $crate::tokens::RArrow };
+    [;] => { $crate::tokens::Semi };
+    [<<] => { $crate::tokens::Shl };
+    [<<=] => { $crate::tokens::ShlEq };
+    [>>] => { $crate::tokens::Shr };
+    [>>=] => { $crate::tokens::ShrEq };
+    [/] => { $crate::tokens::Slash };
+    [/=] => { $crate::tokens::SlashEq };
+    [*] => { $crate::tokens::Star };
+    [*=] => { $crate::tokens::StarEq };
+    [~] => { $crate::tokens::Tilde };
+}
@@ -1,6 +1,6 @@
 //! Utility functions
 
-use proc_macro2::{Literal, Punct, TokenStream, TokenTree};
+use proc_macro2::{Group, Literal, Punct, TokenStream, TokenTree};
 
 use super::{RustSpan, RustToken};
 
@@ -39,6 +39,59 @@ pub fn into_vec(stream: TokenStream) -> Vec<(RustToken, RustSpan)> {
         .collect()
 }
 
+/// Rebuild a `TokenStream` from flat tokens, as produced by [`into_vec`]. Groups take the span of
+/// their opening delimiter, joined with their closing one where possible.
+pub fn from_slice(tokens: &[(RustToken, RustSpan)]) -> TokenStream {
+    let mut stack = vec![(TokenStream::new(), None)];
+
+    for (tok, span) in tokens {
+        match tok {
+            RustToken::StartDelim(_) => stack.push((TokenStream::new(), Some(*span))),
+            RustToken::EndDelim(delim) => {
+                let (inner, open) = stack.pop().expect("Unbalanced delimiters in token slice");
+                let mut group = Group::new(*delim, inner);
+                if let Some(open) = open {
+                    group.set_span(*open.join_lossy(*span));
+                }
+                stack
+                    .last_mut()
+                    .expect("Unbalanced delimiters in token slice")
+                    .0
+                    .extend([TokenTree::Group(group)]);
+            }
+            RustToken::DocComment(doc) => {
+                stack
+                    .last_mut()
+                    .unwrap()
+                    .0
+                    .extend(from_slice(&doc.tokens()));
+            }
+            _ => {
+                let tree = match tok.clone() {
+                    RustToken::Ident(ident) => TokenTree::Ident(ident),
+                    RustToken::Punct(punct) => TokenTree::Punct(punct),
+                    RustToken::Literal(lit) => TokenTree::Literal(lit),
+                    _ => unreachable!(),
+                };
+                stack.last_mut().unwrap().0.extend([tree]);
+            }
+        }
+    }
+
+    stack
+        .pop()
+        .filter(|_| stack.is_empty())
+        .expect("Unbalanced delimiters in token slice")
+        .0
+}
+
+/// Get the span covering a run of flat tokens, or `None` if there are no tokens
+pub fn span_of(tokens: &[(RustToken, RustSpan)]) -> Option<RustSpan> {
+    let first = tokens.first()?.1;
+    let last = tokens.last()?.1;
+    Some(first.join_lossy(last))
+}
+
 /// Compare two `Literal`s
 pub fn lit_eq(left: &Literal, right: &Literal) -> bool {
     // This seems sufficient - literals preserve their text into to_string well
@@ -50,3 +103,131 @@ pub fn punct_eq(left: &Punct, right: &Punct) -> bool {
     // to_string would lose spacing info
     left.as_char() == right.as_char() && left.spacing() == right.spacing()
 }
+
+/// Strict and reserved Rust keywords, which can't be used as plain identifiers
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Check whether an identifier is a Rust keyword
+pub fn is_keyword(ident: &str) -> bool {
+    KEYWORDS.contains(&ident)
+}
+
+/// Check whether an identifier can be used as a segment of a path in a type or expression. This
+/// allows any non-keyword, as well as `self`, `Self`, `super` and `crate`.
+pub fn is_path_segment(ident: &str) -> bool {
+    !is_keyword(ident) || matches!(ident, "self" | "Self" | "super" | "crate")
+}
+
+/// Get the number of single-character insertions, deletions or substitutions needed to turn one
+/// string into another
+pub fn edit_distance(left: &str, right: &str) -> usize {
+    let right = right.chars().collect::<Vec<_>>();
+    let mut prev = (0..=right.len()).collect::<Vec<_>>();
+    let mut cur = vec![0; right.len() + 1];
+
+    for (i, l) in left.chars().enumerate() {
+        cur[0] = i + 1;
+        for (j, r) in right.iter().enumerate() {
+            let sub = prev[j] + usize::from(l != *r);
+            cur[j + 1] = sub.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[right.len()]
+}
+
+/// Find the candidate closest to `name`, if any is close enough to likely be a typo of it
+pub fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max = usize::max(1, name.chars().count() / 3);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(dist, _)| *dist <= max)
+        .min_by_key(|(dist, _)| *dist)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Get the value of a string literal, such as `"a\nb"` or `r#"a"b"#`, with escapes processed.
+/// Returns `None` if the literal isn't a string or contains an invalid escape.
+pub fn unescape_str(lit: &Literal) -> Option<String> {
+    let repr = lit.to_string();
+
+    if let Some(raw) = repr.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = raw[hashes..].strip_prefix('"')?;
+        let end = body.rfind('"')?;
+        return (body[end + 1..].len() >= hashes).then(|| body[..end].to_string());
+    }
+
+    let body = repr.strip_prefix('"')?;
+    let body = &body[..body.rfind('"')?];
+    let mut out = String::with_capacity(body.len());
+    let mut chars = body.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => out.push('\n'),
+            'r' => out.push('\r'),
+            't' => out.push('\t'),
+            '0' => out.push('\0'),
+            '\\' => out.push('\\'),
+            '\'' => out.push('\''),
+            '"' => out.push('"'),
+            'x' => {
+                let hex = [chars.next()?, chars.next()?].iter().collect::<String>();
+                out.push(char::from(u8::from_str_radix(&hex, 16).ok()?));
+            }
+            'u' => {
+                if chars.next()? != '{' {
+                    return None;
+                }
+                let hex = chars
+                    .by_ref()
+                    .take_while(|&c| c != '}')
+                    .filter(|&c| c != '_')
+                    .collect::<String>();
+                out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+            }
+            '\n' => while chars.next_if(|c| c.is_whitespace()).is_some() {},
+            _ => return None,
+        }
+    }
+
+    Some(out)
+}
+
+/// Get the value of an integer literal, such as `0x1F_u8` or `1_000`. Returns `None` if the
+/// literal isn't an integer or doesn't fit in an `i128`.
+pub fn parse_int(lit: &Literal) -> Option<i128> {
+    let repr = lit.to_string().replace('_', "");
+
+    let (radix, digits) = match repr.get(..2) {
+        Some("0x") => (16, &repr[2..]),
+        Some("0o") => (8, &repr[2..]),
+        Some("0b") => (2, &repr[2..]),
+        _ => (10, &*repr),
+    };
+    let end = digits
+        .find(|c: char| !c.is_digit(radix))
+        .unwrap_or(digits.len());
+    let (digits, suffix) = digits.split_at(end);
+
+    if digits.is_empty() || !(suffix.is_empty() || suffix.starts_with(['i', 'u'])) {
+        return None;
+    }
+    i128::from_str_radix(digits, radix).ok()
+}
@@ -0,0 +1,233 @@
+//! Non-fatal warnings about the input of a macro, such as deprecated forms
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned, ToTokens};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::RustSpan;
+
+/// A warning about the input of a macro. Unlike a [`RustError`][crate::RustError], it doesn't
+/// stop the macro from generating output.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::Warning;
+/// # use proc_macro2::Span;
+/// let warning = Warning::new(Span::call_site().into(), "`units!` is deprecated")
+///     .with_help("use `unit!` instead");
+///
+/// assert_eq!(warning.message(), "`units!` is deprecated");
+/// assert_eq!(warning.help(), ["use `unit!` instead"]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Warning {
+    /// The span the warning points at
+    span: RustSpan,
+    /// The main message
+    message: String,
+    /// Extra notes to show alongside the message
+    notes: Vec<String>,
+    /// Suggestions to show alongside the message
+    help: Vec<String>,
+}
+
+impl Warning {
+    /// Create a warning with a message at the given span
+    #[must_use]
+    pub fn new(span: RustSpan, message: impl ToString) -> Warning {
+        Warning {
+            span,
+            message: message.to_string(),
+            notes: Vec::new(),
+            help: Vec::new(),
+        }
+    }
+
+    /// Add a note to this warning
+    #[must_use]
+    pub fn with_note(mut self, note: impl ToString) -> Warning {
+        self.notes.push(note.to_string());
+        self
+    }
+
+    /// Add a help note to this warning, such as ``use `unit!` instead``
+    #[must_use]
+    pub fn with_help(mut self, help: impl ToString) -> Warning {
+        self.help.push(help.to_string());
+        self
+    }
+
+    /// Get the span this warning points at
+    #[must_use]
+    pub fn span(&self) -> RustSpan {
+        self.span
+    }
+
+    /// Get the main message of this warning
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Get the notes attached to this warning
+    #[must_use]
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    /// Get the help notes attached to this warning
+    #[must_use]
+    pub fn help(&self) -> &[String] {
+        &self.help
+    }
+
+    /// Render this warning as tokens which make the compiler warn at its span on stable. Macros
+    /// can't emit warnings directly, so this defines a `#[deprecated]` item and uses it, making
+    /// the compiler report ``use of deprecated unit struct `_::warning` `` with our message.
+    ///
+    /// The tokens are an item, so they can go alongside items or statements. A macro which expands
+    /// to an expression must put them in a block, as `{ #warning #output }`, or use
+    /// [`to_compile_warning_expr`][Warning::to_compile_warning_expr].
+    #[must_use]
+    pub fn to_compile_warning(&self) -> TokenStream {
+        let expr = self.to_compile_warning_expr();
+        quote_spanned!(*self.span=> const _: () = #expr;)
+    }
+
+    /// Render this warning like [`to_compile_warning`][Warning::to_compile_warning], but as a
+    /// block expression evaluating to `()`, for use in expression position
+    #[must_use]
+    pub fn to_compile_warning_expr(&self) -> TokenStream {
+        let message = self.to_string();
+        quote_spanned! {*self.span=>
+            {
+                #[deprecated(note = #message)]
+                #[allow(non_camel_case_types)]
+                struct warning;
+                let _ = warning;
+            }
+        }
+    }
+
+    /// Report this warning from a proc-macro. With the `nightly` feature, while a macro is
+    /// expanding, it's emitted as a native compiler warning and no tokens are returned.
+    /// Otherwise, it's rendered with [`to_compile_warning`][Warning::to_compile_warning], so
+    /// expression macros must wrap it as `{ #warning #output }`.
+    #[must_use]
+    pub fn emit(&self) -> TokenStream {
+        #[cfg(feature = "nightly")]
+        if crate::diagnostic::emit_warning(self) {
+            return TokenStream::new();
+        }
+        self.to_compile_warning()
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        for note in &self.notes {
+            write!(f, "\nnote: {}", note)?;
+        }
+        for help in &self.help {
+            write!(f, "\nhelp: {}", help)?;
+        }
+        Ok(())
+    }
+}
+
+/// A shared collection of warnings, which parsers can push to as they go. Clones share the same
+/// collection, so a parser can hold one while the macro renders them afterwards.
+///
+/// Warnings are pushed as soon as the parser producing them succeeds, even if an enclosing
+/// parser later backtracks, so push them from parsers which are committed to, or after parsing.
+///
+/// # Examples
+///
+/// ```
+/// # use chumsky_proc::prelude::*;
+/// # use chumsky_proc::{Warning, Warnings};
+/// # use chumsky_proc::syntax::Stmt;
+/// # use chumsky::prelude::*;
+/// # use quote::quote;
+/// let warnings = Warnings::new();
+/// let parser = keyword::<RustError>("units")
+///     .map_with_span({
+///         let warnings = warnings.clone();
+///         move |_, span| {
+///             warnings.push(Warning::new(span, "`units` is deprecated").with_help("use `unit`"));
+///         }
+///     })
+///     .or(keyword("unit"))
+///     .ignore_then(ident());
+///
+/// parser.parse(stream_from_tokens(quote!(units kg))).unwrap();
+/// assert_eq!(warnings.len(), 1);
+///
+/// // Outside of a proc-macro, warnings render as uses of a `#[deprecated]` item
+/// let tokens = warnings.emit().to_string();
+/// assert!(tokens.contains("deprecated"));
+/// assert!(warnings.is_empty());
+///
+/// // Macros which expand to an expression keep their output usable as one
+/// parser.parse(stream_from_tokens(quote!(units m))).unwrap();
+/// let tokens = warnings.emit_expr(quote!(1 + 2));
+/// let block = chumsky_proc::syntax::stmt::block::<RustError>()
+///     .then_ignore(end())
+///     .parse(stream_from_tokens(tokens))
+///     .unwrap();
+/// assert!(matches!(block.stmts[..], [Stmt::Item(_), Stmt::Expr { semi_token: None, .. }]));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Warnings(Rc<RefCell<Vec<Warning>>>);
+
+impl Warnings {
+    /// Create an empty collection
+    #[must_use]
+    pub fn new() -> Warnings {
+        Warnings::default()
+    }
+
+    /// Add a warning to the collection
+    pub fn push(&self, warning: Warning) {
+        self.0.borrow_mut().push(warning);
+    }
+
+    /// Get the number of warnings in the collection
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Check whether the collection has no warnings
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    /// Remove every warning from the collection, returning them in the order they were pushed
+    #[must_use]
+    pub fn take(&self) -> Vec<Warning> {
+        self.0.take()
+    }
+
+    /// Remove every warning from the collection and report them with [`Warning::emit`], returning
+    /// the tokens to include alongside the macro's output. The tokens are items, so a macro which
+    /// expands to an expression should use [`emit_expr`][Warnings::emit_expr] instead.
+    #[must_use]
+    pub fn emit(&self) -> TokenStream {
+        self.take().iter().map(Warning::emit).collect()
+    }
+
+    /// Like [`emit`][Warnings::emit], but for a macro which expands to an expression. The
+    /// warnings and the macro's output are wrapped in a block, `{ #warnings #output }`, which
+    /// can go anywhere `output` could.
+    #[must_use]
+    pub fn emit_expr(&self, output: impl ToTokens) -> TokenStream {
+        let warnings = self.emit();
+        quote!({ #warnings #output })
+    }
+}